@@ -31,7 +31,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut client = Client::new(&server, "MyProject/1.0 (by username on e621)")?;
 
     if let (Some(login), Some(Ok(Some(api_key)))) = (login, api_key) {
-        client.login(login, api_key);
+        client.login((login, api_key));
     }
 
     let tags = input!("Search terms: ")?.unwrap_or_else(|| "".into());
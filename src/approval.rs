@@ -0,0 +1,130 @@
+use {
+    super::{
+        client::{query_param, Client, PageStream},
+        error::Result as Rs621Result,
+    },
+    chrono::{offset::Utc, DateTime},
+    futures::prelude::*,
+    serde::Deserialize,
+};
+
+/// Structure representing the approval of a pending post by a moderator.
+#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[non_exhaustive]
+pub struct PostApproval {
+    pub id: u64,
+    /// The approver.
+    pub user_id: u64,
+    pub post_id: u64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Client {
+    /// Returns a Stream over post approvals, newest first. Only the approvals of the given post
+    /// and/or by the given user are returned if they're set.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut approvals = client.post_approvals(None, Some(17633)).take(20);
+    ///
+    /// while let Some(approval) = approvals.next().await {
+    ///     println!("Approved post #{}", approval?.post_id);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn post_approvals(
+        &self,
+        post_id: Option<u64>,
+        user_id: Option<u64>,
+    ) -> impl Stream<Item = Rs621Result<PostApproval>> + '_ {
+        let mut endpoint = String::from("/post_approvals.json?limit=320");
+
+        if let Some(post_id) = post_id {
+            endpoint.push('&');
+            endpoint.push_str(&query_param("search[post_id]", &post_id.to_string()));
+        }
+
+        if let Some(user_id) = user_id {
+            endpoint.push('&');
+            endpoint.push_str(&query_param("search[user_id]", &user_id.to_string()));
+        }
+
+        PageStream::new(self, endpoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn post_approvals() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let expected: Vec<Rs621Result<PostApproval>> =
+            serde_json::from_str::<Vec<PostApproval>>(include_str!("mocked/post_approvals.json"))
+                .unwrap()
+                .into_iter()
+                .map(Ok)
+                .collect();
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact(
+                    "/post_approvals.json?limit=320&search%5Buser_id%5D=17633&page=1".into(),
+                ),
+            )
+            .with_body(include_str!("mocked/post_approvals.json"))
+            .create(),
+            // have the next page be empty to end the stream
+            mock(
+                "GET",
+                Matcher::Exact(
+                    "/post_approvals.json?limit=320&search%5Buser_id%5D=17633&page=2".into(),
+                ),
+            )
+            .with_body(r#"{"post_approvals":[]}"#)
+            .create(),
+        ];
+
+        assert_eq!(
+            client
+                .post_approvals(None, Some(17633))
+                .collect::<Vec<_>>()
+                .await,
+            expected
+        );
+    }
+
+    #[tokio::test]
+    async fn post_approvals_filters_combine() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock(
+            "GET",
+            Matcher::Exact(
+                "/post_approvals.json?limit=320&search%5Bpost_id%5D=4621373&search%5Buser_id%5D=17633&page=1"
+                    .into(),
+            ),
+        )
+        .with_body("[]")
+        .create();
+
+        assert_eq!(
+            client
+                .post_approvals(Some(4621373), Some(17633))
+                .collect::<Vec<_>>()
+                .await,
+            vec![]
+        );
+    }
+}
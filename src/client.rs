@@ -10,27 +10,308 @@ mod rate_limit;
 #[path = "client/dummy_rate_limit.rs"]
 mod rate_limit;
 
+#[path = "client/page_stream.rs"]
+mod page_stream;
+
+pub(crate) use page_stream::PageStream;
+
+#[path = "client/multi_client.rs"]
+mod multi_client;
+
+#[path = "client/search_cache.rs"]
+mod search_cache;
+
+pub(crate) use search_cache::SearchCache;
+
+pub use multi_client::MultiClient;
+
+pub(crate) use multi_client::{dedup_posts, Interleave};
+
+#[cfg(feature = "retry")]
+#[path = "client/retry.rs"]
+mod retry;
+
+#[cfg(feature = "retry")]
+pub use retry::{ExponentialBackoff, RetryPolicy};
+
+/// Environment variables read by [`Credentials::from_env`].
+const ENV_USERNAME: &str = "E621_USERNAME";
+const ENV_API_KEY: &str = "E621_API_KEY";
+
 /// Forced cool down duration performed at every request. E621 allows at most 2 requests per second,
 /// so the lowest safe value we can have here is 500 ms.
 #[cfg(feature = "rate-limit")]
 const REQ_COOLDOWN_DURATION: std::time::Duration = std::time::Duration::from_millis(600);
 
-use futures::Future;
-use reqwest::{Response, Url};
+use futures::{
+    future::{self, Either},
+    Future, FutureExt,
+};
+use reqwest::{
+    header::{ETAG, IF_NONE_MATCH},
+    Method, Response, StatusCode, Url,
+};
 use serde::Serialize;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
 
 use {
     super::error::{Error, Result},
+    derivative::Derivative,
     reqwest::header::HeaderMap,
 };
 
+use std::time::Duration;
+
+#[cfg(not(target_family = "wasm"))]
+use std::time::Instant;
+
+#[cfg(target_family = "wasm")]
+use web_time::Instant;
+
+/// A response body along with the ETag the server sent for it, if any. Pass the ETag back to the
+/// `*_if_changed` methods to only get the body again if it changed in the meantime.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct ETagged<T> {
+    pub data: T,
+    pub etag: Option<String>,
+}
+
+/// Formats a `key=value` query parameter, percent-encoding both sides. Everything but
+/// alphanumerics and `-._~` is escaped, including spaces, `+`, `&` and `#`, so values reach the
+/// server as they are.
+pub(crate) fn query_param(key: &str, value: &str) -> String {
+    format!(
+        "{}={}",
+        urlencoding::encode(key),
+        urlencoding::encode(value)
+    )
+}
+
+/// Appends `query`, URL encoded, to the query parameters of `endpoint`.
+pub(crate) fn endpoint_with_query<T: Serialize>(endpoint: &str, query: &T) -> Result<String> {
+    let query = serde_urlencoded::to_string(query).map_err(|e| Error::Serial(format!("{}", e)))?;
+
+    if query.is_empty() {
+        Ok(endpoint.into())
+    } else if endpoint.contains('?') {
+        Ok(format!("{}&{}", endpoint, query))
+    } else {
+        Ok(format!("{}?{}", endpoint, query))
+    }
+}
+
+/// Turns an error from reqwest into [`Error::CannotSendRequest`]. reqwest's errors include the
+/// request URL, which may contain the credentials, so it's left out.
+pub(crate) fn send_error(e: reqwest::Error) -> Error {
+    Error::CannotSendRequest(format!("{}", e.without_url()))
+}
+
+/// Returns a copy of `url` without the `login` and `api_key` query parameters, so that it can be
+/// logged or shown without leaking the credentials.
+fn strip_credentials(url: &Url) -> Url {
+    let is_credential = |key: &str| key == "login" || key == "api_key";
+
+    if !url.query_pairs().any(|(key, _)| is_credential(&key)) {
+        return url.clone();
+    }
+
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !is_credential(key))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+
+    let mut stripped = url.clone();
+
+    if pairs.is_empty() {
+        stripped.set_query(None);
+    } else {
+        stripped.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+
+    stripped
+}
+
+/// Information about a completed request, passed to the hook set with
+/// [`ClientBuilder::on_request`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RequestInfo {
+    /// The HTTP method, e.g. `GET`.
+    pub method: String,
+    /// The path and query of the request, without the credentials.
+    pub endpoint: String,
+    /// The HTTP status code, or `None` if no response was received.
+    pub status: Option<u16>,
+    /// Time from sending the request to receiving the response headers.
+    pub duration: Duration,
+}
+
+/// Hook called after every request, see [`ClientBuilder::on_request`].
+pub type RequestHook = Arc<dyn Fn(&RequestInfo) + Send + Sync>;
+
+/// Passes the outcome of a request to the hook, if there's one.
+fn report_request(
+    hook: &Option<RequestHook>,
+    method: &str,
+    url: &Url,
+    res: &Result<Response>,
+    started: Instant,
+) {
+    if let Some(hook) = hook {
+        let url = strip_credentials(url);
+        let endpoint = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().into(),
+        };
+
+        hook(&RequestInfo {
+            method: method.into(),
+            endpoint,
+            status: res.as_ref().ok().map(|res| res.status().as_u16()),
+            duration: started.elapsed(),
+        });
+    }
+}
+
+/// Logs the outcome of a request at debug level.
+#[cfg(feature = "tracing")]
+fn trace_response(method: &str, url: &Url, res: &Result<Response>, started: Instant) {
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    match res {
+        Ok(res) => tracing::debug!(
+            method,
+            url = %strip_credentials(url),
+            status = res.status().as_u16(),
+            elapsed_ms,
+            "request finished"
+        ),
+        Err(e) => tracing::debug!(
+            method,
+            url = %strip_credentials(url),
+            error = %e,
+            elapsed_ms,
+            "request failed"
+        ),
+    }
+}
+
+/// Reads the body of `res` as JSON. Fails with [`Error::ResponseTooLarge`] as soon as the body is
+/// known to be larger than `max_bytes`, if set, without reading the rest of it.
+async fn read_json(res: Response, max_bytes: Option<usize>) -> Result<serde_json::Value> {
+    let limit = match max_bytes {
+        Some(limit) => limit,
+        None => {
+            return res
+                .json()
+                .await
+                .map_err(|e| Error::Serial(format!("{}", e.without_url())))
+        }
+    };
+
+    if matches!(res.content_length(), Some(len) if len > limit as u64) {
+        return Err(Error::ResponseTooLarge { limit });
+    }
+
+    let body = read_body(res, limit).await?;
+    serde_json::from_slice(&body).map_err(|e| Error::Serial(format!("{}", e)))
+}
+
+#[cfg(not(target_family = "wasm"))]
+async fn read_body(mut res: Response, limit: usize) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+
+    while let Some(chunk) = res.chunk().await.map_err(send_error)? {
+        if body.len() + chunk.len() > limit {
+            return Err(Error::ResponseTooLarge { limit });
+        }
+
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(body)
+}
+
+// responses can't be read incrementally in WASM, so the limit is only checked once the body has
+// been received, in addition to the `Content-Length` check
+#[cfg(target_family = "wasm")]
+async fn read_body(res: Response, limit: usize) -> Result<Vec<u8>> {
+    let body = res.bytes().await.map_err(send_error)?;
+
+    if body.len() > limit {
+        Err(Error::ResponseTooLarge { limit })
+    } else {
+        Ok(body.to_vec())
+    }
+}
+
+/// Turns an unsuccessful response into an error. The `errors` object of 422 responses is parsed
+/// into [`Error::Validation`], other responses give an [`Error::Http`] with the server's reason
+/// (and the URL without credentials).
+async fn response_error(url: Url, res: Response, max_bytes: Option<usize>) -> Error {
+    let code = res.status().as_u16();
+    let body = read_json(res, max_bytes).await.ok();
+
+    if code == 422 {
+        let errors = body
+            .as_ref()
+            .and_then(|v| v["errors"].as_object())
+            .map(|errors| {
+                errors
+                    .iter()
+                    .map(|(field, messages)| {
+                        // usually a list of messages, but accept a lone message too
+                        let messages = match messages {
+                            serde_json::Value::Array(messages) => messages
+                                .iter()
+                                .filter_map(|m| m.as_str().map(ToString::to_string))
+                                .collect(),
+                            other => other
+                                .as_str()
+                                .map(ToString::to_string)
+                                .into_iter()
+                                .collect(),
+                        };
+
+                        (field.clone(), messages)
+                    })
+                    .collect()
+            });
+
+        if let Some(errors) = errors {
+            return Error::Validation { errors };
+        }
+    }
+
+    Error::Http {
+        url: strip_credentials(&url),
+        code,
+        reason: body.and_then(|v| v["reason"].as_str().map(ToString::to_string)),
+    }
+}
+
 #[cfg(any(target_arch = "wasm32", target_arch = "wasm64"))]
-fn create_header_map<T: AsRef<[u8]>>(_user_agent: T) -> Result<HeaderMap> {
-    Ok(HeaderMap::new())
+fn create_header_map<T: AsRef<[u8]>>(
+    _user_agent: T,
+    accept_language: Option<&str>,
+) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    insert_accept_language(&mut headers, accept_language)?;
+
+    Ok(headers)
 }
 
 #[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
-fn create_header_map<T: AsRef<[u8]>>(user_agent: T) -> Result<HeaderMap> {
+fn create_header_map<T: AsRef<[u8]>>(
+    user_agent: T,
+    accept_language: Option<&str>,
+) -> Result<HeaderMap> {
     if user_agent.as_ref() == b"" {
         Err(Error::CannotCreateClient(String::from(
             "User Agent mustn't be empty",
@@ -42,11 +323,39 @@ fn create_header_map<T: AsRef<[u8]>>(user_agent: T) -> Result<HeaderMap> {
             reqwest::header::HeaderValue::from_bytes(user_agent.as_ref())
                 .map_err(|e| Error::InvalidHeaderValue(format!("{}", e)))?,
         );
+        insert_accept_language(&mut headers, accept_language)?;
 
         Ok(headers)
     }
 }
 
+/// Sets the Accept-Language header, if there's one. Only the characters browsers send it with
+/// without a CORS preflight request are allowed, which are enough for any list of languages
+/// (e.g. `fr-CH, fr;q=0.9, en;q=0.8, *;q=0.5`).
+fn insert_accept_language(headers: &mut HeaderMap, accept_language: Option<&str>) -> Result<()> {
+    let value = match accept_language {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+
+    let is_valid = |c: char| c.is_ascii_alphanumeric() || " *,-.;=".contains(c);
+
+    if value.trim().is_empty() || !value.chars().all(is_valid) {
+        return Err(Error::InvalidHeaderValue(format!(
+            "invalid Accept-Language: {:?}",
+            value
+        )));
+    }
+
+    headers.insert(
+        reqwest::header::ACCEPT_LANGUAGE,
+        reqwest::header::HeaderValue::from_str(value)
+            .map_err(|e| Error::InvalidHeaderValue(format!("{}", e)))?,
+    );
+
+    Ok(())
+}
+
 #[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
 fn create_extra_query<T: AsRef<[u8]>>(_user_agent: T) -> Result<Vec<(String, String)>> {
     Ok(Default::default())
@@ -66,27 +375,279 @@ pub(crate) type QueryFuture = Box<dyn Future<Output = Result<serde_json::Value>>
 #[cfg(any(target_arch = "wasm32", target_arch = "wasm64"))]
 pub(crate) type QueryFuture = Box<dyn Future<Output = Result<serde_json::Value>>>;
 
-/// Client struct.
-#[derive(Debug)]
-pub struct Client {
-    pub(crate) client: reqwest::Client,
-    rate_limit: rate_limit::RateLimit,
-    url: Url,
-    headers: HeaderMap,
-    extra_query: Vec<(String, String)>,
-    login: Option<(String, String)>,
+#[cfg(all(
+    any(feature = "timeout", feature = "retry"),
+    not(target_family = "wasm")
+))]
+async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
 }
 
-impl Client {
-    fn create(url: &str, user_agent: impl AsRef<[u8]>, proxy: Option<&str>) -> Result<Self> {
+#[cfg(all(any(feature = "timeout", feature = "retry"), target_family = "wasm"))]
+async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await
+}
+
+/// Fails with [`Error::Timeout`] if `fut` doesn't complete within `timeout`.
+#[cfg(feature = "timeout")]
+async fn with_timeout<F, T>(timeout: Duration, fut: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    match future::select(Box::pin(fut), Box::pin(sleep(timeout))).await {
+        Either::Left((res, _)) => res,
+        Either::Right(((), _)) => Err(Error::Timeout),
+    }
+}
+
+/// Calls `attempt` until it succeeds, or until `policy` gives up. Errors are returned right away
+/// if there's no policy.
+#[cfg(feature = "retry")]
+async fn with_retries<F, Fut, T>(policy: Option<Arc<dyn RetryPolicy>>, mut attempt: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut failures = 0;
+
+    loop {
+        let error = match attempt().await {
+            Err(e) => e,
+            res => return res,
+        };
+
+        failures += 1;
+
+        match policy
+            .as_ref()
+            .and_then(|p| p.retry_after(failures, &error))
+        {
+            Some(delay) => sleep(delay).await,
+            None => return Err(error),
+        }
+    }
+}
+
+/// A username and API key, used to log in with [`Client::login`]. The API key is left out of the
+/// `Debug` output.
+#[derive(Derivative, Clone, PartialEq, Eq)]
+#[derivative(Debug)]
+pub struct Credentials {
+    pub username: String,
+    #[derivative(Debug = "ignore")]
+    pub api_key: String,
+}
+
+impl Credentials {
+    pub fn new(username: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Credentials {
+            username: username.into(),
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Reads the credentials from the `E621_USERNAME` and `E621_API_KEY` environment variables.
+    /// Returns `None` if neither is set, and fails with [`Error::IncompleteCredentials`] if only
+    /// one of them is.
+    ///
+    /// ```no_run
+    /// # use rs621::client::{Client, Credentials};
+    /// # fn main() -> rs621::error::Result<()> {
+    /// let mut client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// if let Some(credentials) = Credentials::from_env()? {
+    ///     client.login(credentials);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn from_env() -> Result<Option<Self>> {
+        Credentials::from_vars(
+            std::env::var(ENV_USERNAME).ok(),
+            std::env::var(ENV_API_KEY).ok(),
+        )
+    }
+
+    fn from_vars(username: Option<String>, api_key: Option<String>) -> Result<Option<Self>> {
+        match (username, api_key) {
+            (Some(username), Some(api_key)) => Ok(Some(Credentials { username, api_key })),
+            (None, None) => Ok(None),
+            (Some(_), None) => Err(Error::IncompleteCredentials {
+                missing: String::from(ENV_API_KEY),
+            }),
+            (None, Some(_)) => Err(Error::IncompleteCredentials {
+                missing: String::from(ENV_USERNAME),
+            }),
+        }
+    }
+}
+
+impl From<(String, String)> for Credentials {
+    fn from((username, api_key): (String, String)) -> Self {
+        Credentials { username, api_key }
+    }
+}
+
+impl From<(&str, &str)> for Credentials {
+    fn from((username, api_key): (&str, &str)) -> Self {
+        Credentials::new(username, api_key)
+    }
+}
+
+/// Builder for a [`Client`] with non-default settings, created with [`Client::builder`].
+///
+/// ```no_run
+/// # use rs621::client::Client;
+/// # fn main() -> Result<(), rs621::error::Error> {
+/// let client = Client::builder("https://e926.net", "MyProject/1.0 (by username on e621)")
+///     .max_concurrent(4)
+///     .build()?;
+/// # Ok(()) }
+/// ```
+#[derive(Derivative, Clone)]
+#[derivative(Debug)]
+pub struct ClientBuilder {
+    url: String,
+    user_agent: Vec<u8>,
+    proxy: Option<String>,
+    max_concurrent: Option<usize>,
+    use_http_verbs: bool,
+    validate_responses: bool,
+    max_response_bytes: Option<usize>,
+    #[derivative(Debug = "ignore")]
+    on_request: Option<RequestHook>,
+    search_cache: Option<(usize, Duration)>,
+    accept_language: Option<String>,
+    #[cfg(feature = "timeout")]
+    page_timeout: Option<Duration>,
+    #[cfg(feature = "retry")]
+    #[derivative(Debug = "ignore")]
+    retry_policy: Option<Arc<dyn RetryPolicy>>,
+}
+
+impl ClientBuilder {
+    /// Send all requests through the given proxy.
+    pub fn proxy(mut self, proxy: &str) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    /// Limit the number of requests in flight at the same time, across all the streams and
+    /// futures using the client. Requests over the limit wait for a slot before being sent. There
+    /// is no limit by default.
+    pub fn max_concurrent(mut self, max: usize) -> Self {
+        self.max_concurrent = Some(max);
+        self
+    }
+
+    /// Send genuine DELETE and PUT requests, instead of POST requests with a `_method` parameter.
+    /// The latter are used by default because e621's CORS headers don't allow DELETE and PUT,
+    /// which matters in browsers, but other clients or servers may prefer the real verbs.
+    pub fn use_http_verbs(mut self, enabled: bool) -> Self {
+        self.use_http_verbs = enabled;
+        self
+    }
+
+    /// Check that the posts sent by the server make sense (e.g. that their score adds up) before
+    /// returning them, failing with [`Error::Invalid`] otherwise. Useful to catch bad data early
+    /// when archiving posts. Disabled by default.
+    pub fn validate_responses(mut self, enabled: bool) -> Self {
+        self.validate_responses = enabled;
+        self
+    }
+
+    /// Fail with [`Error::ResponseTooLarge`] instead of reading response bodies larger than
+    /// `max` bytes, so that a misbehaving server can't exhaust the memory. Search pages can weigh
+    /// a few megabytes, so don't set this too low. There is no limit by default.
+    pub fn max_response_bytes(mut self, max: usize) -> Self {
+        self.max_response_bytes = Some(max);
+        self
+    }
+
+    /// Call `hook` after every request, with its method, endpoint, status code and duration.
+    /// Useful to collect metrics. Credentials are removed from the endpoint.
+    ///
+    /// ```
+    /// # use rs621::client::Client;
+    /// use std::sync::{
+    ///     atomic::{AtomicUsize, Ordering},
+    ///     Arc,
+    /// };
+    ///
+    /// # fn main() -> Result<(), rs621::error::Error> {
+    /// let requests = Arc::new(AtomicUsize::new(0));
+    /// let counter = requests.clone();
+    ///
+    /// let client = Client::builder("https://e926.net", "MyProject/1.0 (by username on e621)")
+    ///     .on_request(Arc::new(move |_| {
+    ///         counter.fetch_add(1, Ordering::Relaxed);
+    ///     }))
+    ///     .build()?;
+    /// # Ok(()) }
+    /// ```
+    pub fn on_request(mut self, hook: RequestHook) -> Self {
+        self.on_request = Some(hook);
+        self
+    }
+
+    /// Keep the results of up to `capacity` searches made with [`Client::cached_post_search`]
+    /// in memory, for `ttl` each, so that the same search isn't sent again in the meantime. The
+    /// oldest results are dropped first when the cache is full. There is no cache by default.
+    ///
+    /// Clones of the client share the cache, but results are only served to clones logged in as
+    /// the same user, or to anonymous clones if the search was made without logging in.
+    pub fn search_cache(mut self, capacity: usize, ttl: Duration) -> Self {
+        self.search_cache = Some((capacity, ttl));
+        self
+    }
+
+    /// Send an Accept-Language header with every request, to ask for content in the given
+    /// languages, e.g. `"fr-CH, fr;q=0.9, en;q=0.8"`. e621 itself doesn't localize anything for
+    /// now, but mirrors may. [`ClientBuilder::build`] fails with [`Error::InvalidHeaderValue`] if
+    /// the value isn't a valid list of languages.
+    pub fn accept_language(mut self, languages: &str) -> Self {
+        self.accept_language = Some(languages.into());
+        self
+    }
+
+    /// Give up on a page of a stream if fetching it, body included, takes longer than `timeout`.
+    /// The stream then yields [`Error::Timeout`], and fetches the same page again if it's polled
+    /// further. There is no timeout by default.
+    #[cfg(feature = "timeout")]
+    pub fn page_timeout(mut self, timeout: Duration) -> Self {
+        self.page_timeout = Some(timeout);
+        self
+    }
+
+    /// Send failed GET requests again according to `policy`, e.g. [`ExponentialBackoff`]. Other
+    /// requests are never retried, since they may have had an effect before failing. Requests
+    /// aren't retried by default.
+    ///
+    /// ```no_run
+    /// # use rs621::client::{Client, ExponentialBackoff};
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), rs621::error::Error> {
+    /// let client = Client::builder("https://e926.net", "MyProject/1.0 (by username on e621)")
+    ///     .retry_policy(ExponentialBackoff::new(5, Duration::from_secs(2)))
+    ///     .build()?;
+    /// # Ok(()) }
+    /// ```
+    #[cfg(feature = "retry")]
+    pub fn retry_policy(mut self, policy: impl RetryPolicy + 'static) -> Self {
+        self.retry_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Create the [`Client`].
+    pub fn build(self) -> Result<Client> {
         let client = reqwest::Client::builder();
-        let client = match proxy {
+        let client = match self.proxy {
             #[cfg(any(target_arch = "wasm32", target_arch = "wasm64"))]
             Some(_) => panic!("proxies are not supported in wasm"),
 
             #[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
             Some(proxy) => {
-                let proxy = reqwest::Proxy::https(proxy)
+                let proxy = reqwest::Proxy::https(&proxy)
                     .map_err(|e| Error::CannotCreateClient(format!("{}", e)))?;
 
                 client.proxy(proxy)
@@ -99,34 +660,157 @@ impl Client {
             .build()
             .map_err(|e| Error::CannotCreateClient(format!("{}", e)))?;
 
+        let concurrency = match self.max_concurrent {
+            Some(0) => {
+                return Err(Error::CannotCreateClient(String::from(
+                    "Maximum concurrent requests mustn't be zero",
+                )))
+            }
+            Some(max) => Some(Arc::new(Semaphore::new(max))),
+            None => None,
+        };
+
         Ok(Client {
             client,
-            url: Url::parse(url)?,
+            url: Url::parse(&self.url)?,
             rate_limit: Default::default(),
-            headers: create_header_map(&user_agent)?,
-            extra_query: create_extra_query(&user_agent)?,
+            concurrency,
+            shutdown: Default::default(),
+            headers: create_header_map(&self.user_agent, self.accept_language.as_deref())?,
+            extra_query: create_extra_query(&self.user_agent)?,
             login: None,
+            use_http_verbs: self.use_http_verbs,
+            validate_responses: self.validate_responses,
+            max_response_bytes: self.max_response_bytes,
+            on_request: self.on_request,
+            search_cache: self
+                .search_cache
+                .filter(|&(capacity, _)| capacity > 0)
+                .map(|(capacity, ttl)| Arc::new(SearchCache::new(capacity, ttl))),
+            #[cfg(feature = "timeout")]
+            page_timeout: self.page_timeout,
+            #[cfg(feature = "retry")]
+            retry_policy: self.retry_policy,
         })
     }
+}
+
+/// Client struct.
+///
+/// Cloning a client is cheap, so it can be handed to spawned tasks instead of being wrapped in an
+/// [`Arc`]. Clones share the rate limit, the limit of concurrent requests and the shutdown state,
+/// so they never send requests faster than a single client would. Logging in or out only affects
+/// the client it's done on.
+#[derive(Derivative, Clone)]
+#[derivative(Debug)]
+pub struct Client {
+    pub(crate) client: reqwest::Client,
+    rate_limit: rate_limit::RateLimit,
+    concurrency: Option<Arc<Semaphore>>,
+    shutdown: Arc<Shutdown>,
+    url: Url,
+    pub(crate) headers: HeaderMap,
+    extra_query: Vec<(String, String)>,
+    use_http_verbs: bool,
+    pub(crate) validate_responses: bool,
+    max_response_bytes: Option<usize>,
+    #[derivative(Debug = "ignore")]
+    on_request: Option<RequestHook>,
+    #[derivative(Debug = "ignore")]
+    pub(crate) search_cache: Option<Arc<SearchCache>>,
+    #[cfg(feature = "timeout")]
+    page_timeout: Option<Duration>,
+    #[cfg(feature = "retry")]
+    #[derivative(Debug = "ignore")]
+    retry_policy: Option<Arc<dyn RetryPolicy>>,
+    login: Option<Credentials>,
+}
+
+#[derive(Debug, Default)]
+struct Shutdown {
+    requested: AtomicBool,
+    notify: Notify,
+}
+
+/// Waits for a request slot, if the number of concurrent requests is limited.
+async fn acquire_slot(concurrency: Option<Arc<Semaphore>>) -> Result<Option<OwnedSemaphorePermit>> {
+    match concurrency {
+        // the semaphore is only ever closed by `Client::shutdown`
+        Some(semaphore) => semaphore
+            .acquire_owned()
+            .await
+            .map(Some)
+            .map_err(|_| Error::Shutdown),
+        None => Ok(None),
+    }
+}
+
+/// Runs `fut` to completion, unless the client is shut down first.
+async fn until_shutdown<F: Future>(shutdown: Arc<Shutdown>, fut: F) -> Result<F::Output> {
+    // register before checking the flag, so that a concurrent `shutdown` can't be missed
+    let notified = shutdown.notify.notified();
+
+    if shutdown.requested.load(Ordering::SeqCst) {
+        return Err(Error::Shutdown);
+    }
+
+    futures::pin_mut!(fut, notified);
+
+    match future::select(fut, notified).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(_) => Err(Error::Shutdown),
+    }
+}
 
+impl Client {
     /// Create a new client with the specified value for the User-Agent header. The API requires a
     /// non-empty User-Agent header for all requests, preferably including your E621 username and
     /// the name of your project.
     pub fn new(url: &str, user_agent: impl AsRef<[u8]>) -> Result<Self> {
-        Client::create(url, user_agent, None)
+        Client::builder(url, user_agent).build()
     }
 
     /// Create a new client with the specified User-Agent header and proxy. The API requires a
     /// non-empty User-Agent header for all requests, preferably including your E621 username and
     /// the name of your project.
     pub fn with_proxy(url: &str, user_agent: impl AsRef<[u8]>, proxy: &str) -> Result<Self> {
-        Client::create(url, user_agent, Some(proxy))
+        Client::builder(url, user_agent).proxy(proxy).build()
+    }
+
+    /// Create a [`ClientBuilder`] with the specified value for the User-Agent header, to configure
+    /// the client further. The API requires a non-empty User-Agent header for all requests,
+    /// preferably including your E621 username and the name of your project.
+    pub fn builder(url: &str, user_agent: impl AsRef<[u8]>) -> ClientBuilder {
+        ClientBuilder {
+            url: url.into(),
+            user_agent: user_agent.as_ref().to_vec(),
+            proxy: None,
+            max_concurrent: None,
+            use_http_verbs: false,
+            validate_responses: false,
+            max_response_bytes: None,
+            on_request: None,
+            search_cache: None,
+            accept_language: None,
+            #[cfg(feature = "timeout")]
+            page_timeout: None,
+            #[cfg(feature = "retry")]
+            retry_policy: None,
+        }
     }
 
     /// Login to the server with the provided username and API key. All subsequent requests will be
     /// sent with the given credentials.
-    pub fn login(&mut self, username: String, api_key: String) {
-        self.login = Some((username, api_key));
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # fn main() -> rs621::error::Result<()> {
+    /// let mut client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// client.login(("username", "api_key"));
+    /// # Ok(()) }
+    /// ```
+    pub fn login(&mut self, credentials: impl Into<Credentials>) {
+        self.login = Some(credentials.into());
     }
 
     /// Remove any login information previously set with [Client::login].
@@ -134,12 +818,115 @@ impl Client {
         self.login = None;
     }
 
+    /// Whether login information is set, i.e. whether requests are sent with credentials. This
+    /// doesn't check the credentials with the server.
+    pub fn is_authenticated(&self) -> bool {
+        self.login.is_some()
+    }
+
+    /// The username given to [Client::login], if any.
+    pub fn username(&self) -> Option<&str> {
+        self.login
+            .as_ref()
+            .map(|credentials| credentials.username.as_str())
+    }
+
+    /// Checks whether the server accepts the given username and API key, by making a request
+    /// that requires to be logged in. The credentials aren't stored, see [`Client::login`] for
+    /// that. Returns `Ok(false)` if the server rejects them, and an error if it can't be reached.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let mut client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// if client.check_credentials("username", "api_key").await? {
+    ///     client.login(("username", "api_key"));
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn check_credentials(&self, username: &str, api_key: &str) -> Result<bool> {
+        // the clone shares the rate limit
+        let mut client = self.clone();
+        client.login((username, api_key));
+
+        match client.get_json_endpoint("/favorites.json?limit=1").await {
+            Ok(_) => Ok(true),
+            Err(Error::Http { code: 401, .. }) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// How long the next request would have to wait because of the rate limit, without
+    /// performing it. Zero if it would be sent right away, or if the `rate-limit` feature is
+    /// disabled.
+    pub fn rate_limit_delay(&self) -> Duration {
+        self.rate_limit.delay()
+    }
+
+    /// Waits for the requests already sent or waiting for the rate limit to complete, so that
+    /// everything is sent before exiting. Requests made after calling this aren't waited for.
+    ///
+    /// The client doesn't buffer any write for now, so without the `rate-limit` feature this
+    /// returns immediately. Batching features added later will send their pending writes here,
+    /// so call it before dropping a client that made writes. Fails with [`Error::Shutdown`] if
+    /// the client was shut down.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let mut client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// client.login(("username", "api_key"));
+    ///
+    /// client.post_favorite(8595).await?;
+    /// client.flush().await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn flush(&self) -> Result<()> {
+        let rate_limit = self.rate_limit.clone();
+
+        until_shutdown(
+            self.shutdown.clone(),
+            async move { rate_limit.idle().await },
+        )
+        .await
+    }
+
+    /// Shut the client down. Requests waiting on the rate limit or in flight are interrupted and
+    /// fail with [`Error::Shutdown`], and so will every request made afterwards, so that streams
+    /// return promptly instead of keeping the application from exiting.
+    pub fn shutdown(&self) {
+        self.shutdown.requested.store(true, Ordering::SeqCst);
+        self.shutdown.notify.notify_waiters();
+
+        if let Some(ref semaphore) = self.concurrency {
+            semaphore.close();
+        }
+    }
+
+    /// Fails with [`Error::Unauthorized`] if no login information is set.
+    pub(crate) fn require_login(&self) -> Result<()> {
+        if self.is_authenticated() {
+            Ok(())
+        } else {
+            Err(Error::Unauthorized)
+        }
+    }
+
+    /// Returns the URL of `endpoint` on the server, without any credentials. Use it to display
+    /// or log URLs: the ones used for requests carry the API key when the client is logged in.
+    pub fn public_url(&self, endpoint: &str) -> Result<Url> {
+        Ok(strip_credentials(&self.url(endpoint)?))
+    }
+
     pub(crate) fn url(&self, endpoint: &str) -> Result<Url, url::ParseError> {
         let mut url = self.url.join(endpoint)?;
-        if let Some((ref login, ref api_key)) = self.login {
+        if let Some(ref credentials) = self.login {
             url.query_pairs_mut()
-                .append_pair("login", login)
-                .append_pair("api_key", api_key);
+                .append_pair("login", &credentials.username)
+                .append_pair("api_key", &credentials.api_key);
         }
 
         for (key, value) in &self.extra_query {
@@ -149,54 +936,87 @@ impl Client {
         Ok(url)
     }
 
-    async fn post_response<T>(&self, endpoint: &str, body: &T) -> Result<Response>
+    pub(crate) async fn post_response<T>(&self, endpoint: &str, body: &T) -> Result<Response>
+    where
+        T: serde::Serialize,
+    {
+        self.form_response(Method::POST, endpoint, Some(body), future::ok)
+            .await
+    }
+
+    /// Sends a request with the given method and form body, if any, and passes the successful
+    /// response to `read`. The request keeps its concurrency slot until `read` completes, so
+    /// that its body is read within the limit too.
+    async fn form_response<T, F, Fut, R>(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<&T>,
+        read: F,
+    ) -> Result<R>
     where
         T: serde::Serialize,
+        F: FnOnce(Response) -> Fut,
+        Fut: Future<Output = Result<R>>,
     {
         let url = self.url(endpoint)?;
-        let mut request = self.client.post(url.clone());
+        let mut request = self.client.request(method.clone(), url.clone());
 
-        if let Some((ref username, ref password)) = self.login {
-            request = request.basic_auth(username, Some(password));
+        // in browsers, the Authorization header would need a CORS preflight request, and the
+        // credentials are already in the query string anyway
+        #[cfg(not(target_family = "wasm"))]
+        if let Some(ref credentials) = self.login {
+            request = request.basic_auth(&credentials.username, Some(&credentials.api_key));
         }
 
-        let request_fut = request
-            .form(body) // `.json(...)` has problems with CORS in WASM.
-            .headers(self.headers.clone())
-            .send();
+        if let Some(body) = body {
+            request = request.form(body); // `.json(...)` has problems with CORS in WASM.
+        }
 
-        self.rate_limit
-            .clone()
-            .check(async move {
-                let res = request_fut
-                    .await
-                    .map_err(|e| Error::CannotSendRequest(format!("{}", e)))?;
-
-                if res.status().is_success() {
-                    Ok(res)
-                } else {
-                    Err(Error::Http {
-                        url,
-                        code: res.status().as_u16(),
-                        reason: match res.json::<serde_json::Value>().await {
-                            Ok(v) => v["reason"].as_str().map(ToString::to_string),
-                            Err(_) => None,
-                        },
-                    })
-                }
-            })
-            .await
+        let request_fut = request.headers(self.headers.clone()).send();
+
+        let concurrency = self.concurrency.clone();
+        let rate_limit = self.rate_limit.clone();
+        let max_bytes = self.max_response_bytes;
+        let on_request = self.on_request.clone();
+
+        until_shutdown(self.shutdown.clone(), async move {
+            let _slot = acquire_slot(concurrency).await?;
+
+            rate_limit
+                .check(async move {
+                    let started = Instant::now();
+
+                    let res = request_fut.await.map_err(send_error);
+
+                    #[cfg(feature = "tracing")]
+                    trace_response(method.as_str(), &url, &res, started);
+
+                    report_request(&on_request, method.as_str(), &url, &res, started);
+
+                    let res = res?;
+
+                    if res.status().is_success() {
+                        read(res).await
+                    } else {
+                        Err(response_error(url, res, max_bytes).await)
+                    }
+                })
+                .await
+        })
+        .await?
     }
 
     pub(crate) async fn post_form<T>(&self, endpoint: &str, body: &T) -> Result<serde_json::Value>
     where
         T: serde::Serialize,
     {
-        self.post_response(endpoint, body)
-            .await?
-            .json()
-            .await
-            .map_err(|e| Error::Serial(format!("{e}")))
+        let max_bytes = self.max_response_bytes;
+
+        self.form_response(Method::POST, endpoint, Some(body), |res| {
+            read_json(res, max_bytes)
+        })
+        .await
     }
 
     pub(crate) async fn delete(&self, endpoint: &str) -> Result<()> {
@@ -205,50 +1025,418 @@ impl Client {
             _method: &'static str,
         }
 
-        // Can't use HTTP DELETE because e621's CORS headers aren't permissive enough. Thankfully
-        // ruby on rails has a workaround for exactly this purpose.
-        self.post_response(endpoint, &Form { _method: "delete" })
-            .await?;
+        if self.use_http_verbs {
+            self.form_response(Method::DELETE, endpoint, None::<&()>, future::ok)
+                .await?;
+        } else {
+            // Can't use HTTP DELETE because e621's CORS headers aren't permissive enough.
+            // Thankfully ruby on rails has a workaround for exactly this purpose.
+            self.post_response(endpoint, &Form { _method: "delete" })
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn put(&self, endpoint: &str) -> Result<()> {
+        #[derive(Serialize)]
+        struct Form {
+            _method: &'static str,
+        }
+
+        if self.use_http_verbs {
+            self.form_response(Method::PUT, endpoint, None::<&()>, future::ok)
+                .await?;
+        } else {
+            // Same workaround as `delete`.
+            self.post_response(endpoint, &Form { _method: "put" })
+                .await?;
+        }
+
         Ok(())
     }
 
+    /// Fetches a page for a stream, applying the page timeout if there's one.
+    pub(crate) fn get_json_page(&self, endpoint: &str) -> std::pin::Pin<QueryFuture> {
+        let fut = self.get_json_endpoint(endpoint);
+
+        #[cfg(feature = "timeout")]
+        if let Some(timeout) = self.page_timeout {
+            return Box::pin(with_timeout(timeout, fut));
+        }
+
+        Box::pin(fut)
+    }
+
     pub fn get_json_endpoint(
         &self,
         endpoint: &str,
     ) -> impl Future<Output = Result<serde_json::Value>> {
-        let url = self.url(endpoint);
-        let request = url
-            .clone()
-            .map(|url| self.client.get(url).headers(self.headers.clone()).send());
-
-        self.rate_limit.clone().check(async move {
-            let res = request?
-                .await
-                .map_err(|e| Error::CannotSendRequest(format!("{}", e)))?;
+        self.get_json_tagged(endpoint, None)
+            .map(|res| res.map(|tagged| tagged.data))
+    }
 
-            if res.status().is_success() {
-                res.json()
-                    .await
-                    .map_err(|e| Error::Serial(format!("{}", e)))
-            } else {
-                Err(Error::Http {
-                    url: url?,
-                    code: res.status().as_u16(),
-                    reason: match res.json::<serde_json::Value>().await {
-                        Ok(v) => v["reason"].as_str().map(ToString::to_string),
-                        Err(_) => None,
-                    },
-                })
-            }
-        })
+    /// Like [`Client::get_json_endpoint`], but sends `If-None-Match` with the ETag of a previous
+    /// response, if there's one. Returns `None` if the server answered that nothing changed since
+    /// then (HTTP 304), which saves downloading the same body again when polling.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let first = client
+    ///     .get_json_endpoint_if_changed("/posts.json?tags=fluffy", None)
+    ///     .await?
+    ///     .unwrap();
+    ///
+    /// match client
+    ///     .get_json_endpoint_if_changed("/posts.json?tags=fluffy", first.etag.as_deref())
+    ///     .await?
+    /// {
+    ///     Some(_) => println!("Something changed!"),
+    ///     None => println!("Nothing new"),
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn get_json_endpoint_if_changed(
+        &self,
+        endpoint: &str,
+        etag: Option<&str>,
+    ) -> Result<Option<ETagged<serde_json::Value>>> {
+        match self.get_json_tagged(endpoint, etag).await {
+            Ok(tagged) => Ok(Some(tagged)),
+            Err(Error::NotModified) => Ok(None),
+            Err(e) => Err(e),
+        }
     }
-}
 
-#[cfg(test)]
+    /// GETs `endpoint`, keeping the ETag of the response. Fails with [`Error::NotModified`] if
+    /// the server answers with HTTP 304.
+    fn get_json_tagged(
+        &self,
+        endpoint: &str,
+        etag: Option<&str>,
+    ) -> impl Future<Output = Result<ETagged<serde_json::Value>>> {
+        let url = self.url(endpoint);
+        let request = url.clone().map(|url| {
+            let mut request = self.client.get(url).headers(self.headers.clone());
+
+            if let Some(etag) = etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+
+            request
+        });
+        let concurrency = self.concurrency.clone();
+        let rate_limit = self.rate_limit.clone();
+        let max_bytes = self.max_response_bytes;
+        let on_request = self.on_request.clone();
+
+        let attempt = move || {
+            // GET requests have no body, so they can always be cloned
+            let request = request
+                .as_ref()
+                .map(|request| request.try_clone().unwrap().send())
+                .map_err(|e| *e);
+            let url = url.clone();
+            let concurrency = concurrency.clone();
+            let rate_limit = rate_limit.clone();
+            let on_request = on_request.clone();
+
+            async move {
+                let _slot = acquire_slot(concurrency).await?;
+
+                rate_limit
+                    .check(async move {
+                        let request = request?;
+
+                        let started = Instant::now();
+
+                        let res = request.await.map_err(send_error);
+
+                        if let Ok(ref url) = url {
+                            #[cfg(feature = "tracing")]
+                            trace_response("GET", url, &res, started);
+
+                            report_request(&on_request, "GET", url, &res, started);
+                        }
+
+                        let res = res?;
+
+                        if res.status() == StatusCode::NOT_MODIFIED {
+                            Err(Error::NotModified)
+                        } else if res.status().is_success() {
+                            let etag = res
+                                .headers()
+                                .get(ETAG)
+                                .and_then(|etag| etag.to_str().ok())
+                                .map(String::from);
+
+                            Ok(ETagged {
+                                data: read_json(res, max_bytes).await?,
+                                etag,
+                            })
+                        } else {
+                            Err(response_error(url?, res, max_bytes).await)
+                        }
+                    })
+                    .await
+            }
+        };
+
+        #[cfg(feature = "retry")]
+        let fut = with_retries(self.retry_policy.clone(), attempt);
+
+        #[cfg(not(feature = "retry"))]
+        let fut = attempt();
+
+        until_shutdown(self.shutdown.clone(), fut).map(|res| res?)
+    }
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
     use mockito::mock;
 
+    #[tokio::test]
+    async fn max_response_bytes() {
+        let client = Client::builder(&mockito::server_url(), b"rs621/unit_test")
+            .max_response_bytes(100)
+            .build()
+            .unwrap();
+
+        let large = format!(r#"{{"posts":[],"padding":"{}"}}"#, "a".repeat(200));
+
+        let _m = [
+            mock("GET", "/posts.json?tags=small")
+                .with_body(r#"{"posts":[]}"#)
+                .create(),
+            mock("GET", "/posts.json?tags=large")
+                .with_body(&large)
+                .create(),
+            mock("GET", "/posts.json?tags=error")
+                .with_status(500)
+                .with_body(&large)
+                .create(),
+        ];
+
+        assert_eq!(
+            client.get_json_endpoint("/posts.json?tags=small").await,
+            Ok(serde_json::json!({ "posts": [] }))
+        );
+        assert_eq!(
+            client.get_json_endpoint("/posts.json?tags=large").await,
+            Err(Error::ResponseTooLarge { limit: 100 })
+        );
+
+        // the body of errors is limited too, it's just not used for the reason
+        let server_url = Url::parse(&mockito::server_url()).unwrap();
+        assert_eq!(
+            client.get_json_endpoint("/posts.json?tags=error").await,
+            Err(Error::Http {
+                url: server_url.join("/posts.json?tags=error").unwrap(),
+                code: 500,
+                reason: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn on_request() {
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let hook_requests = requests.clone();
+
+        let mut client = Client::builder(&mockito::server_url(), b"rs621/unit_test")
+            .on_request(Arc::new(move |info: &RequestInfo| {
+                hook_requests.lock().unwrap().push(info.clone())
+            }))
+            .build()
+            .unwrap();
+        client.login(("foo", "bar"));
+
+        let _m = [
+            mock("GET", "/posts.json?tags=fluffy&login=foo&api_key=bar")
+                .with_body(r#"{"posts":[]}"#)
+                .create(),
+            mock("POST", "/favorites.json?login=foo&api_key=bar")
+                .with_status(404)
+                .create(),
+        ];
+
+        assert!(client
+            .get_json_endpoint("/posts.json?tags=fluffy")
+            .await
+            .is_ok());
+        assert!(client.post_favorite(1).await.is_err());
+
+        let requests = requests.lock().unwrap();
+        let summary: Vec<_> = requests
+            .iter()
+            .map(|info| (info.method.as_str(), info.endpoint.as_str(), info.status))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("GET", "/posts.json?tags=fluffy", Some(200)),
+                ("POST", "/favorites.json", Some(404)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn get_json_endpoint_if_changed() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = [
+            mock("GET", "/posts.json?tags=fluffy")
+                .match_header("if-none-match", mockito::Matcher::Missing)
+                .with_header("etag", r#"W/"abc""#)
+                .with_body(r#"{"posts":[]}"#)
+                .create(),
+            mock("GET", "/posts.json?tags=fluffy")
+                .match_header("if-none-match", r#"W/"abc""#)
+                .with_status(304)
+                .create(),
+        ];
+
+        assert_eq!(
+            client
+                .get_json_endpoint_if_changed("/posts.json?tags=fluffy", None)
+                .await,
+            Ok(Some(ETagged {
+                data: serde_json::json!({ "posts": [] }),
+                etag: Some(String::from(r#"W/"abc""#)),
+            }))
+        );
+        assert_eq!(
+            client
+                .get_json_endpoint_if_changed("/posts.json?tags=fluffy", Some(r#"W/"abc""#))
+                .await,
+            Ok(None)
+        );
+    }
+
+    #[tokio::test]
+    async fn flush() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        assert_eq!(client.flush().await, Ok(()));
+
+        client.shutdown();
+        assert_eq!(client.flush().await, Err(Error::Shutdown));
+    }
+
+    #[cfg(feature = "retry")]
+    #[tokio::test]
+    async fn retry_policy() {
+        let attempts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let policy_attempts = attempts.clone();
+
+        let client = Client::builder(&mockito::server_url(), b"rs621/unit_test")
+            .retry_policy(move |attempt, error: &Error| {
+                policy_attempts.lock().unwrap().push(attempt);
+
+                match error {
+                    Error::Http { code: 503, .. } if attempt < 3 => Some(Duration::ZERO),
+                    _ => None,
+                }
+            })
+            .build()
+            .unwrap();
+
+        let _m = [
+            mock("GET", "/posts.json?tags=down")
+                .with_status(503)
+                .create(),
+            mock("GET", "/posts.json?tags=missing")
+                .with_status(404)
+                .create(),
+        ];
+
+        assert!(matches!(
+            client.get_json_endpoint("/posts.json?tags=down").await,
+            Err(Error::Http { code: 503, .. })
+        ));
+        assert_eq!(*attempts.lock().unwrap(), vec![1, 2, 3]);
+
+        attempts.lock().unwrap().clear();
+
+        assert!(matches!(
+            client.get_json_endpoint("/posts.json?tags=missing").await,
+            Err(Error::Http { code: 404, .. })
+        ));
+        assert_eq!(*attempts.lock().unwrap(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn check_credentials() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = [
+            mock("GET", "/favorites.json?limit=1&login=foo&api_key=bar")
+                .with_body(r#"{"posts":[]}"#)
+                .create(),
+            mock("GET", "/favorites.json?limit=1&login=foo&api_key=wrong")
+                .with_status(401)
+                .with_body(r#"{"success":false,"reason":"invalid API key"}"#)
+                .create(),
+            mock("GET", "/favorites.json?limit=1&login=foo&api_key=down")
+                .with_status(503)
+                .create(),
+        ];
+
+        assert_eq!(client.check_credentials("foo", "bar").await, Ok(true));
+        assert_eq!(client.check_credentials("foo", "wrong").await, Ok(false));
+        assert!(matches!(
+            client.check_credentials("foo", "down").await,
+            Err(Error::Http { code: 503, .. })
+        ));
+
+        assert!(!client.is_authenticated());
+    }
+
+    #[test]
+    fn credentials_from_vars() {
+        let var = |s: &str| Some(String::from(s));
+
+        assert_eq!(
+            Credentials::from_vars(var("foo"), var("bar")),
+            Ok(Some(Credentials::new("foo", "bar")))
+        );
+        assert_eq!(Credentials::from_vars(None, None), Ok(None));
+        assert_eq!(
+            Credentials::from_vars(var("foo"), None),
+            Err(Error::IncompleteCredentials {
+                missing: String::from("E621_API_KEY")
+            })
+        );
+        assert_eq!(
+            Credentials::from_vars(None, var("bar")),
+            Err(Error::IncompleteCredentials {
+                missing: String::from("E621_USERNAME")
+            })
+        );
+    }
+
+    #[test]
+    fn credentials_debug_hides_api_key() {
+        let debug = format!("{:?}", Credentials::new("foo", "secret_key"));
+
+        assert!(debug.contains("foo"));
+        assert!(!debug.contains("secret_key"));
+    }
+
+    #[test]
+    fn query_param_escapes_reserved_characters() {
+        assert_eq!(
+            query_param("search[name_matches]", "rock & roll+#1"),
+            "search%5Bname_matches%5D=rock%20%26%20roll%2B%231"
+        );
+    }
+
     #[tokio::test]
     async fn get_json_endpoint_http_error() {
         let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
@@ -271,6 +1459,218 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn post_form_validation_error() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock("POST", "/comments.json")
+            .with_status(422)
+            .with_body(
+                r#"{"errors":{"body":["can't be blank","is too short"],"post":"must exist"}}"#,
+            )
+            .create();
+
+        let res = client.post_form("/comments.json", &[("body", "")]).await;
+
+        let mut errors = std::collections::HashMap::new();
+        errors.insert(
+            String::from("body"),
+            vec![String::from("can't be blank"), String::from("is too short")],
+        );
+        errors.insert(String::from("post"), vec![String::from("must exist")]);
+
+        assert_eq!(res, Err(Error::Validation { errors }));
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Validation failed: body: can't be blank, body: is too short, post: must exist"
+        );
+    }
+
+    #[test]
+    fn login_logout() {
+        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        assert!(!client.is_authenticated());
+        assert_eq!(client.username(), None);
+
+        client.login(("foo", "bar"));
+        assert!(client.is_authenticated());
+        assert_eq!(client.username(), Some("foo"));
+
+        client.logout();
+        assert!(!client.is_authenticated());
+        assert_eq!(client.username(), None);
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn requests_accept_compressed_responses() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock("GET", "/posts.json")
+            .match_header("accept-encoding", mockito::Matcher::Regex("gzip".into()))
+            .match_header("accept-encoding", mockito::Matcher::Regex("br".into()))
+            .with_body("[]")
+            .create();
+
+        assert_eq!(
+            client.get_json_endpoint("/posts.json").await,
+            Ok(serde_json::json!([]))
+        );
+    }
+
+    #[test]
+    fn strip_credentials_removes_login_and_api_key() {
+        let url =
+            Url::parse("https://e926.net/posts.json?tags=fluffy&login=foo&api_key=bar").unwrap();
+        assert_eq!(
+            strip_credentials(&url).as_str(),
+            "https://e926.net/posts.json?tags=fluffy"
+        );
+
+        let url = Url::parse("https://e926.net/posts.json?login=foo&api_key=bar").unwrap();
+        assert_eq!(
+            strip_credentials(&url).as_str(),
+            "https://e926.net/posts.json"
+        );
+
+        let url = Url::parse("https://e926.net/posts.json?tags=fluffy%20rating%3As").unwrap();
+        assert_eq!(strip_credentials(&url), url);
+    }
+
+    #[test]
+    fn public_url() {
+        let mut client = Client::new("https://e926.net", b"rs621/unit_test").unwrap();
+        client.login(("foo", "bar"));
+
+        assert_eq!(
+            client
+                .public_url("/posts.json?tags=fluffy")
+                .unwrap()
+                .as_str(),
+            "https://e926.net/posts.json?tags=fluffy"
+        );
+    }
+
+    #[tokio::test]
+    async fn http_error_hides_credentials() {
+        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        client.login(("foo", "bar"));
+
+        let _m = mock("GET", "/posts/1.json?login=foo&api_key=bar")
+            .with_status(404)
+            .create();
+
+        let server_url = Url::parse(&mockito::server_url()).unwrap();
+
+        assert_eq!(
+            client.get_json_endpoint("/posts/1.json").await,
+            Err(crate::error::Error::Http {
+                url: server_url.join("/posts/1.json").unwrap(),
+                code: 404,
+                reason: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn send_error_hides_credentials() {
+        // nothing listens on port 1, so the connection is refused
+        let builder = Client::builder("http://127.0.0.1:1", b"rs621/unit_test");
+        #[cfg(feature = "retry")]
+        let builder = builder.retry_policy(|_: u32, _: &Error| None);
+
+        let mut client = builder.build().unwrap();
+        client.login(("foo", "bar"));
+
+        match client.get_json_endpoint("/posts.json").await {
+            Err(Error::CannotSendRequest(message)) => {
+                assert!(!message.contains("api_key"), "{}", message);
+                assert!(!message.contains("bar"), "{}", message);
+            }
+            res => panic!("expected CannotSendRequest, got {:?}", res),
+        }
+    }
+
+    #[tokio::test]
+    async fn delete_and_put_use_method_override_by_default() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = [
+            mock("POST", "/favorites/1.json")
+                .match_body("_method=delete")
+                .create(),
+            mock("POST", "/dmails/1/mark_as_read.json")
+                .match_body("_method=put")
+                .create(),
+        ];
+
+        assert_eq!(client.delete("/favorites/1.json").await, Ok(()));
+        assert_eq!(client.put("/dmails/1/mark_as_read.json").await, Ok(()));
+    }
+
+    /// Every write must be a POST with a form body, which browsers send without a CORS preflight
+    /// request. Keep this in sync with the list in the crate's documentation.
+    #[tokio::test]
+    async fn writes_are_cors_safe() {
+        use crate::post::{VoteDir, VoteMethod};
+
+        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        client.login(("foo", "bar"));
+
+        let form_post = |path: &str| {
+            mock(
+                "POST",
+                mockito::Matcher::Exact(format!("{}?login=foo&api_key=bar", path)),
+            )
+            .match_header("content-type", "application/x-www-form-urlencoded")
+        };
+
+        let _m = [
+            form_post("/favorites.json")
+                .with_body(include_str!("mocked/favorite.json"))
+                .create(),
+            form_post("/favorites/1.json")
+                .match_body("_method=delete")
+                .create(),
+            form_post("/posts/1/votes.json")
+                .with_body(r#"{"score":41,"up":44,"down":-3,"our_score":1}"#)
+                .create(),
+            form_post("/moderator/post/posts/1/delete.json").create(),
+            form_post("/post_sets/1/add_posts.json").create(),
+            form_post("/post_sets/1/remove_posts.json").create(),
+            form_post("/dmails/1/mark_as_read.json")
+                .match_body("_method=put")
+                .create(),
+        ];
+
+        assert!(client.post_favorite(1).await.is_ok());
+        assert_eq!(client.post_unfavorite(1).await, Ok(()));
+        assert!(client
+            .post_vote(1, VoteMethod::Set, VoteDir::Up)
+            .await
+            .is_ok());
+        assert_eq!(client.delete_post(1, "duplicate", None).await, Ok(()));
+        assert_eq!(client.add_to_set(1, &[2]).await, Ok(()));
+        assert_eq!(client.remove_from_set(1, &[2]).await, Ok(()));
+        assert_eq!(client.mark_dmail_read(1).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn delete_and_put_with_http_verbs() {
+        let client = Client::builder(&mockito::server_url(), b"rs621/unit_test")
+            .use_http_verbs(true)
+            .build()
+            .unwrap();
+
+        let _m = [
+            mock("DELETE", "/favorites/2.json").create(),
+            mock("PUT", "/dmails/2/mark_as_read.json").create(),
+        ];
+
+        assert_eq!(client.delete("/favorites/2.json").await, Ok(()));
+        assert_eq!(client.put("/dmails/2/mark_as_read.json").await, Ok(()));
+    }
+
     #[tokio::test]
     async fn get_json_endpoint_success() {
         let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
@@ -312,18 +1712,201 @@ mod tests {
         assert!(Client::with_proxy(&mockito::server_url(), b"rs621/unit/test", "").is_err());
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn max_concurrent_requests() {
+        let started = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let hook_started = started.clone();
+
+        let client = Client::builder(&mockito::server_url(), b"rs621/unit_test")
+            .max_concurrent(1)
+            .on_request(Arc::new(move |_| {
+                hook_started.fetch_add(1, Ordering::SeqCst);
+            }))
+            .build()
+            .unwrap();
+
+        let _m = mock("GET", "/post/show.json?id=8595")
+            .with_body(r#"{"dummy":"json"}"#)
+            .create();
+
+        // take the only slot, like a request in flight would
+        let slot = acquire_slot(client.concurrency.clone()).await.unwrap();
+
+        let request = tokio::spawn({
+            let client = client.clone();
+            async move { client.get_json_endpoint("/post/show.json?id=8595").await }
+        });
+
+        // plenty of time for the request to complete, if it wasn't waiting for the slot
+        tokio::task::spawn_blocking(|| std::thread::sleep(Duration::from_millis(200)))
+            .await
+            .unwrap();
+        assert_eq!(started.load(Ordering::SeqCst), 0);
+
+        drop(slot);
+
+        assert!(request.await.unwrap().is_ok());
+        assert_eq!(started.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn max_concurrent_requests_cannot_be_zero() {
+        assert!(Client::builder(&mockito::server_url(), b"rs621/unit_test")
+            .max_concurrent(0)
+            .build()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn shutdown_interrupts_requests() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock("GET", "/post/show.json?id=8595")
+            .with_body(r#"{"dummy":"json"}"#)
+            .create();
+
+        // the request is in flight (or waiting on the rate limit) when the client is shut down
+        let (res, _) = future::join(client.get_json_endpoint("/post/show.json?id=8595"), async {
+            client.shutdown()
+        })
+        .await;
+
+        assert_eq!(res, Err(Error::Shutdown));
+
+        // following requests fail right away
+        assert_eq!(
+            client.get_json_endpoint("/post/show.json?id=8595").await,
+            Err(Error::Shutdown)
+        );
+    }
+
+    #[cfg(feature = "rate-limit")]
+    #[tokio::test]
+    async fn shutdown_wakes_rate_limited_requests() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock("GET", "/post/show.json?id=8595")
+            .with_body(r#"{"dummy":"json"}"#)
+            .create();
+
+        assert!(client
+            .get_json_endpoint("/post/show.json?id=8595")
+            .await
+            .is_ok());
+
+        // the next request has to wait for the cool down, shutting down shouldn't
+        let start = std::time::Instant::now();
+        let (res, _) = future::join(client.get_json_endpoint("/post/show.json?id=8595"), async {
+            client.shutdown()
+        })
+        .await;
+
+        assert_eq!(res, Err(Error::Shutdown));
+        assert!(start.elapsed() < REQ_COOLDOWN_DURATION);
+    }
+
+    #[cfg(feature = "rate-limit")]
+    #[tokio::test]
+    async fn rate_limit_delay() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        assert_eq!(client.rate_limit_delay(), Duration::ZERO);
+
+        let _m = mock("GET", "/post/show.json?id=8595")
+            .with_body(r#"{"dummy":"json"}"#)
+            .create();
+
+        assert!(client
+            .get_json_endpoint("/post/show.json?id=8595")
+            .await
+            .is_ok());
+
+        let delay = client.rate_limit_delay();
+        assert!(delay > Duration::ZERO);
+        assert!(delay <= REQ_COOLDOWN_DURATION);
+    }
+
+    #[cfg(feature = "rate-limit")]
+    #[tokio::test]
+    async fn clones_share_rate_limit() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        let clone = client.clone();
+
+        let _m = mock("GET", "/post/show.json?id=8595")
+            .with_body(r#"{"dummy":"json"}"#)
+            .create();
+
+        assert!(client
+            .get_json_endpoint("/post/show.json?id=8595")
+            .await
+            .is_ok());
+
+        assert!(clone.rate_limit_delay() > Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn shutdown_fails_requests_waiting_for_a_slot() {
+        let client = Client::builder(&mockito::server_url(), b"rs621/unit_test")
+            .max_concurrent(1)
+            .build()
+            .unwrap();
+
+        client.shutdown();
+
+        assert_eq!(
+            client.get_json_endpoint("/post/show.json?id=8595").await,
+            Err(Error::Shutdown)
+        );
+    }
+
     #[tokio::test]
     async fn create_header_map_works() {
-        assert!(create_header_map(b"rs621/unit_test").is_ok());
+        assert!(create_header_map(b"rs621/unit_test", None).is_ok());
     }
 
     #[tokio::test]
     async fn create_header_map_requires_valid_user_agent() {
-        assert!(create_header_map(b"\n").is_err());
+        assert!(create_header_map(b"\n", None).is_err());
+    }
+
+    #[tokio::test]
+    async fn create_header_map_accept_language() {
+        let headers =
+            create_header_map(b"rs621/unit_test", Some("fr-CH, fr;q=0.9, *;q=0.5")).unwrap();
+        assert_eq!(
+            headers[reqwest::header::ACCEPT_LANGUAGE],
+            "fr-CH, fr;q=0.9, *;q=0.5"
+        );
+
+        assert!(!create_header_map(b"rs621/unit_test", None)
+            .unwrap()
+            .contains_key(reqwest::header::ACCEPT_LANGUAGE));
+
+        assert!(create_header_map(b"rs621/unit_test", Some("")).is_err());
+        assert!(create_header_map(b"rs621/unit_test", Some("en\r\nX-Foo: bar")).is_err());
+        assert!(create_header_map(b"rs621/unit_test", Some("en_US")).is_err());
+    }
+
+    #[tokio::test]
+    async fn accept_language_is_sent() {
+        let client = Client::builder(&mockito::server_url(), b"rs621/unit_test")
+            .accept_language("fr, en;q=0.5")
+            .build()
+            .unwrap();
+
+        let _m = mock("GET", "/wiki_pages.json")
+            .match_header("accept-language", "fr, en;q=0.5")
+            .with_body("[]")
+            .create();
+
+        assert_eq!(
+            client.get_json_endpoint("/wiki_pages.json").await,
+            Ok(serde_json::json!([]))
+        );
     }
 
     #[tokio::test]
     async fn create_header_map_requires_non_empty_user_agent() {
-        assert!(create_header_map(b"").is_err());
+        assert!(create_header_map(b"", None).is_err());
     }
 }
@@ -1,9 +1,17 @@
 use futures::Future;
 
+use std::time::Duration;
+
 #[derive(Debug, Clone, Default)]
 pub struct RateLimit {}
 
 impl RateLimit {
+    pub fn delay(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    pub async fn idle(&self) {}
+
     pub async fn check<F, R>(self, fut: F) -> R
     where
         F: Future<Output = R>,
@@ -4,6 +4,7 @@ use futures::lock::{Mutex, MutexGuard};
 
 use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 
 use web_time::Instant;
 
@@ -37,10 +38,33 @@ impl RateLimit {
                 }
             };
 
+            #[cfg(feature = "tracing")]
+            tracing::trace!(
+                delay_ms = (deadline - now).as_millis() as u64,
+                "waiting for the rate limit"
+            );
+
             gloo_timers::future::sleep(deadline - now).await;
         }
     }
 
+    /// How long a request made now would have to wait. A request in flight holds the lock and
+    /// sets a new deadline once it completes, so count a whole cool down in that case.
+    pub fn delay(&self) -> Duration {
+        match self.deadline.try_lock() {
+            Some(guard) => guard
+                .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+                .unwrap_or_default(),
+            None => REQ_COOLDOWN_DURATION,
+        }
+    }
+
+    /// Waits for the requests holding or waiting for the lock to complete, without delaying the
+    /// next one.
+    pub async fn idle(&self) {
+        drop(self.deadline.lock().await);
+    }
+
     pub async fn check<F, R>(self, fut: F) -> R
     where
         F: Future<Output = R>,
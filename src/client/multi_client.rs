@@ -0,0 +1,201 @@
+use super::Client;
+
+use crate::{
+    error::Result,
+    post::{Post, Query},
+};
+
+use {
+    futures::{
+        prelude::*,
+        task::{Context, Poll},
+    },
+    std::{collections::HashSet, pin::Pin},
+};
+
+/// Interleaves several streams in a round-robin fashion: one item from the first stream, then one
+/// from the second one, and so on. Streams are dropped as they end.
+#[derive(Debug)]
+pub(crate) struct Interleave<S> {
+    streams: Vec<S>,
+    next: usize,
+}
+
+impl<S> Interleave<S> {
+    pub(crate) fn new(streams: Vec<S>) -> Self {
+        Interleave { streams, next: 0 }
+    }
+}
+
+impl<S: Stream + Unpin> Stream for Interleave<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        let this = self.get_mut();
+
+        while !this.streams.is_empty() {
+            let i = this.next % this.streams.len();
+
+            match this.streams[i].poll_next_unpin(cx) {
+                Poll::Ready(Some(item)) => {
+                    this.next = i + 1;
+                    return Poll::Ready(Some(item));
+                }
+
+                // the next stream takes its place
+                Poll::Ready(None) => {
+                    this.streams.remove(i);
+                    this.next = i;
+                }
+
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        Poll::Ready(None)
+    }
+}
+
+/// Drops the posts whose id was already seen in the stream. Errors are kept.
+pub(crate) fn dedup_posts<S>(stream: S) -> impl Stream<Item = Result<Post>>
+where
+    S: Stream<Item = Result<Post>>,
+{
+    let mut seen = HashSet::new();
+
+    stream.filter(move |res| {
+        future::ready(match res {
+            Ok(post) => seen.insert(post.id),
+            Err(_) => true,
+        })
+    })
+}
+
+/// A set of clients queried together, e.g. to search both e926 and e621.
+#[derive(Debug)]
+pub struct MultiClient {
+    clients: Vec<Client>,
+}
+
+impl MultiClient {
+    /// Create a `MultiClient` from the given clients. Their order matters, see
+    /// [`MultiClient::post_search`].
+    ///
+    /// ```no_run
+    /// # use rs621::client::{Client, MultiClient};
+    /// # fn main() -> rs621::error::Result<()> {
+    /// let client = MultiClient::new(vec![
+    ///     Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?,
+    ///     Client::new("https://e621.net", "MyProject/1.0 (by username on e621)")?,
+    /// ]);
+    /// # Ok(()) }
+    /// ```
+    pub fn new(clients: Vec<Client>) -> Self {
+        MultiClient { clients }
+    }
+
+    /// The clients making up this `MultiClient`.
+    pub fn clients(&self) -> &[Client] {
+        &self.clients
+    }
+
+    /// Runs the same search on every client and merges the results.
+    ///
+    /// The results are interleaved in a round-robin fashion, in the order the clients were given:
+    /// the first post of the first client, then the first post of the second client, and so on. A
+    /// post that was already yielded by a previous client is skipped, so the first client to
+    /// return a post wins. Once a client runs out of posts (or fails), the others keep going.
+    ///
+    /// ```no_run
+    /// # use rs621::client::{Client, MultiClient};
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = MultiClient::new(vec![
+    ///     Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?,
+    ///     Client::new("https://e621.net", "MyProject/1.0 (by username on e621)")?,
+    /// ]);
+    ///
+    /// let mut post_stream = client.post_search(&["fluffy"][..]).take(20);
+    ///
+    /// while let Some(post) = post_stream.next().await {
+    ///     println!("Post #{}", post?.id);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn post_search<'a, T: Into<Query>>(
+        &'a self,
+        tags: T,
+    ) -> impl Stream<Item = Result<Post>> + 'a {
+        let query = tags.into();
+
+        dedup_posts(Interleave::new(
+            self.clients
+                .iter()
+                .map(|client| client.post_search(query.clone()))
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn interleave() {
+        let streams = vec![
+            stream::iter(vec![1, 2, 3]),
+            stream::iter(vec![10]),
+            stream::iter(vec![100, 200]),
+        ];
+
+        assert_eq!(
+            Interleave::new(streams).collect::<Vec<_>>().await,
+            vec![1, 10, 100, 2, 200, 3]
+        );
+    }
+
+    #[tokio::test]
+    async fn post_search_dedup() {
+        // both clients talk to the same server, so every post is seen twice
+        let client = MultiClient::new(vec![
+            Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap(),
+            Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap(),
+        ]);
+
+        let response_json = include_str!("../mocked/id_8595_535_2105_1470.json");
+        let expected: Vec<Result<Post>> = serde_json::from_str::<serde_json::Value>(response_json)
+            .unwrap()["posts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|post| Ok(serde_json::from_value(post.clone()).unwrap()))
+            .collect();
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact("/posts.json?limit=320&page=1&tags=fluffy".into()),
+            )
+            .with_body(response_json)
+            .create(),
+            mock(
+                "GET",
+                Matcher::Regex(r"^/posts\.json\?limit=320&page=b".into()),
+            )
+            .with_body(r#"{"posts":[]}"#)
+            .create(),
+        ];
+
+        assert_eq!(
+            client
+                .post_search(&["fluffy"][..])
+                .collect::<Vec<_>>()
+                .await,
+            expected
+        );
+    }
+}
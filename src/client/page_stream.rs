@@ -0,0 +1,156 @@
+use super::{Client, QueryFuture};
+
+use crate::error::{Error, Result};
+
+use {
+    derivative::Derivative,
+    futures::{
+        prelude::*,
+        task::{Context, Poll},
+    },
+    serde::de::DeserializeOwned,
+    std::pin::Pin,
+};
+
+/// A stream over the items of an endpoint returning a JSON array per page, fetching page after
+/// page until one comes back empty.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub(crate) struct PageStream<'a, T> {
+    client: &'a Client,
+    endpoint: String,
+
+    query_url: Option<String>,
+    #[derivative(Debug = "ignore")]
+    query_future: Option<Pin<QueryFuture>>,
+
+    page: u64,
+    #[derivative(Debug = "ignore")]
+    chunk: Vec<Result<T>>,
+    ended: bool,
+}
+
+impl<'a, T> PageStream<'a, T> {
+    /// `endpoint` may already contain query parameters, the page number is appended to them.
+    pub(crate) fn new(client: &'a Client, endpoint: String) -> Self {
+        PageStream {
+            client,
+            endpoint,
+
+            query_url: None,
+            query_future: None,
+
+            page: 1,
+            chunk: Vec::new(),
+            ended: false,
+        }
+    }
+}
+
+/// Parses a page. Some endpoints answer with `{"<items>": []}` instead of `[]` when there are no
+/// results, so that case is handled too.
+fn parse_page<T: DeserializeOwned>(body: serde_json::Value) -> Vec<Result<T>> {
+    if let Some(object) = body.as_object() {
+        if object
+            .values()
+            .all(|v| matches!(v.as_array(), Some(items) if items.is_empty()))
+        {
+            return Vec::new();
+        }
+    }
+
+    match serde_json::from_value::<Vec<T>>(body) {
+        Ok(res) => res.into_iter().rev().map(Ok).collect(),
+        Err(e) => vec![Err(Error::Serial(format!("{}", e)))],
+    }
+}
+
+impl<'a, T> Stream for PageStream<'a, T>
+where
+    T: DeserializeOwned + Unpin,
+{
+    type Item = Result<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<T>>> {
+        enum QueryPollRes {
+            Pending,
+            Err(crate::error::Error),
+            NotFetching,
+        }
+
+        let this = self.get_mut();
+
+        loop {
+            // poll the pending query future if there's any
+            let query_status = if let Some(ref mut fut) = this.query_future {
+                match fut.as_mut().poll(cx) {
+                    Poll::Ready(res) => {
+                        // the future is finished, drop it
+                        this.query_future = None;
+
+                        match res {
+                            Ok(body) => {
+                                // put everything in the chunk
+                                this.chunk = parse_page(body);
+
+                                // mark the stream as ended if there was no items
+                                this.ended = this.chunk.is_empty();
+                                QueryPollRes::NotFetching
+                            }
+
+                            // a timed out page is fetched again if the stream is polled further
+                            Err(Error::Timeout) => {
+                                this.page -= 1;
+                                QueryPollRes::Err(Error::Timeout)
+                            }
+
+                            // if there was an error, stream it and mark the stream as ended
+                            Err(e) => {
+                                this.ended = true;
+                                QueryPollRes::Err(e)
+                            }
+                        }
+                    }
+
+                    Poll::Pending => QueryPollRes::Pending,
+                }
+            } else {
+                QueryPollRes::NotFetching
+            };
+
+            match query_status {
+                QueryPollRes::Err(e) => return Poll::Ready(Some(Err(e))),
+                QueryPollRes::Pending => return Poll::Pending,
+                QueryPollRes::NotFetching if this.ended => {
+                    // the stream ended because:
+                    // 1. there was an error
+                    // 2. there's simply no more elements
+                    return Poll::Ready(None);
+                }
+                QueryPollRes::NotFetching if !this.chunk.is_empty() => {
+                    // stream an item
+                    return Poll::Ready(this.chunk.pop());
+                }
+                QueryPollRes::NotFetching => {
+                    // we need to load a new chunk of items
+                    let url = format!(
+                        "{}{}page={}",
+                        this.endpoint,
+                        if this.endpoint.contains('?') {
+                            '&'
+                        } else {
+                            '?'
+                        },
+                        this.page,
+                    );
+                    this.page += 1;
+                    this.query_url = Some(url);
+
+                    // get the JSON
+                    this.query_future =
+                        Some(this.client.get_json_page(this.query_url.as_ref().unwrap()));
+                }
+            }
+        }
+    }
+}
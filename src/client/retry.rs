@@ -0,0 +1,140 @@
+use crate::error::Error;
+
+use std::time::Duration;
+
+/// Decides whether a failed request should be sent again, see
+/// [`ClientBuilder::retry_policy`](super::ClientBuilder::retry_policy).
+///
+/// It's implemented for closures taking the same arguments as [`RetryPolicy::retry_after`].
+pub trait RetryPolicy: Send + Sync {
+    /// Returns how long to wait before sending the request again, or `None` to give up and
+    /// return `error`. `attempt` is the number of times the request failed so far, starting at 1.
+    fn retry_after(&self, attempt: u32, error: &Error) -> Option<Duration>;
+}
+
+impl<F> RetryPolicy for F
+where
+    F: Fn(u32, &Error) -> Option<Duration> + Send + Sync,
+{
+    fn retry_after(&self, attempt: u32, error: &Error) -> Option<Duration> {
+        self(attempt, error)
+    }
+}
+
+/// Retries requests that failed because of the server (HTTP 5xx), the rate limit (HTTP 429) or
+/// the connection, doubling the delay after every attempt. Other errors, like validation errors,
+/// aren't retried since sending the same request again wouldn't help.
+///
+/// The default policy retries up to 3 times, waiting 1 second at first, and never more than 30
+/// seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExponentialBackoff {
+    max_retries: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl ExponentialBackoff {
+    /// Retries up to `max_retries` times, waiting `base_delay` before the first retry.
+    pub fn new(max_retries: u32, base_delay: Duration) -> Self {
+        ExponentialBackoff {
+            max_retries,
+            base_delay,
+            max_delay: Duration::from_secs(30),
+        }
+    }
+
+    /// Never wait longer than `max_delay` between two attempts.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    fn is_retryable(error: &Error) -> bool {
+        match error {
+            Error::Http { code, .. } => *code == 429 || *code >= 500,
+            Error::CannotSendRequest(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        ExponentialBackoff::new(3, Duration::from_secs(1))
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn retry_after(&self, attempt: u32, error: &Error) -> Option<Duration> {
+        if attempt > self.max_retries || !ExponentialBackoff::is_retryable(error) {
+            return None;
+        }
+
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let delay = self
+            .base_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay);
+
+        Some(delay.min(self.max_delay))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn http_error(code: u16) -> Error {
+        Error::Http {
+            url: "https://e926.net/posts.json".parse().unwrap(),
+            code,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn exponential_backoff_delays() {
+        let policy =
+            ExponentialBackoff::new(5, Duration::from_secs(1)).max_delay(Duration::from_secs(6));
+        let error = http_error(503);
+
+        let delays: Vec<_> = (1..=6).map(|n| policy.retry_after(n, &error)).collect();
+
+        assert_eq!(
+            delays,
+            vec![
+                Some(Duration::from_secs(1)),
+                Some(Duration::from_secs(2)),
+                Some(Duration::from_secs(4)),
+                Some(Duration::from_secs(6)),
+                Some(Duration::from_secs(6)),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn exponential_backoff_retryable_errors() {
+        let policy = ExponentialBackoff::default();
+
+        assert!(policy.retry_after(1, &http_error(500)).is_some());
+        assert!(policy.retry_after(1, &http_error(429)).is_some());
+        assert!(policy
+            .retry_after(1, &Error::CannotSendRequest(String::from("reset")))
+            .is_some());
+
+        assert_eq!(policy.retry_after(1, &http_error(404)), None);
+        assert_eq!(policy.retry_after(1, &http_error(422)), None);
+        assert_eq!(
+            policy.retry_after(
+                1,
+                &Error::Validation {
+                    errors: Default::default()
+                }
+            ),
+            None
+        );
+        assert_eq!(policy.retry_after(1, &Error::Unauthorized), None);
+    }
+}
@@ -0,0 +1,52 @@
+use super::Instant;
+
+use crate::post::Post;
+
+use std::{collections::VecDeque, sync::Mutex, time::Duration};
+
+/// Cache of search results, see [`ClientBuilder::search_cache`](super::ClientBuilder::search_cache).
+#[derive(Debug)]
+pub(crate) struct SearchCache {
+    capacity: usize,
+    ttl: Duration,
+    /// Oldest entries first. The lock is never held across an `await`.
+    entries: Mutex<VecDeque<(String, Instant, Vec<Post>)>>,
+}
+
+impl SearchCache {
+    pub(crate) fn new(capacity: usize, ttl: Duration) -> Self {
+        SearchCache {
+            capacity,
+            ttl,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Returns the posts cached for `key`, unless they expired.
+    pub(crate) fn get(&self, key: &str) -> Option<Vec<Post>> {
+        let mut entries = self.entries.lock().unwrap();
+
+        // entries are sorted by age, so the expired ones are at the front
+        while matches!(entries.front(), Some((_, at, _)) if at.elapsed() >= self.ttl) {
+            entries.pop_front();
+        }
+
+        entries
+            .iter()
+            .find(|(k, _, _)| k == key)
+            .map(|(_, _, posts)| posts.clone())
+    }
+
+    /// Caches `posts` for `key`, evicting the oldest entry if the cache is full.
+    pub(crate) fn insert(&self, key: String, posts: Vec<Post>) {
+        let mut entries = self.entries.lock().unwrap();
+
+        entries.retain(|(k, _, _)| *k != key);
+
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+
+        entries.push_back((key, Instant::now(), posts));
+    }
+}
@@ -3,6 +3,7 @@ use super::REQ_COOLDOWN_DURATION;
 use std::future::Future;
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::sync::{Mutex, MutexGuard};
 use tokio::time::{sleep_until, Instant};
@@ -38,10 +39,33 @@ impl RateLimit {
                 }
             };
 
+            #[cfg(feature = "tracing")]
+            tracing::trace!(
+                delay_ms = (deadline - now).as_millis() as u64,
+                "waiting for the rate limit"
+            );
+
             sleep_until(deadline).await;
         }
     }
 
+    /// How long a request made now would have to wait. A request in flight holds the lock and
+    /// sets a new deadline once it completes, so count a whole cool down in that case.
+    pub fn delay(&self) -> Duration {
+        match self.deadline.try_lock() {
+            Ok(guard) => guard
+                .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+                .unwrap_or_default(),
+            Err(_) => REQ_COOLDOWN_DURATION,
+        }
+    }
+
+    /// Waits for the requests holding or waiting for the lock to complete, without delaying the
+    /// next one.
+    pub async fn idle(&self) {
+        drop(self.deadline.lock().await);
+    }
+
     pub async fn check<F, R>(self, fut: F) -> R
     where
         F: Future<Output = R>,
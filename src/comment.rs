@@ -0,0 +1,99 @@
+use {
+    super::{
+        client::{query_param, Client, PageStream},
+        error::Result as Rs621Result,
+    },
+    chrono::{offset::Utc, DateTime},
+    futures::prelude::*,
+    serde::Deserialize,
+};
+
+/// Structure representing a comment on a post.
+#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[non_exhaustive]
+pub struct Comment {
+    pub id: u64,
+    pub post_id: u64,
+    pub creator_id: u64,
+    pub creator_name: String,
+    pub body: String,
+    pub score: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub is_hidden: bool,
+    pub is_sticky: bool,
+    /// Set when a moderator marked the comment, e.g. `"warning"`, `"record"` or `"ban"`.
+    pub warning_type: Option<String>,
+}
+
+impl Client {
+    /// Returns a Stream over the comments made on any post, newest first.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut comments = client.recent_comments().take(50);
+    ///
+    /// while let Some(comment) = comments.next().await {
+    ///     let comment = comment?;
+    ///     println!("{} on post #{}", comment.creator_name, comment.post_id);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn recent_comments(&self) -> impl Stream<Item = Rs621Result<Comment>> + '_ {
+        PageStream::new(
+            self,
+            format!(
+                "/comments.json?limit=320&group_by=comment&{}",
+                query_param("search[order]", "id_desc"),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn recent_comments() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let expected: Vec<Rs621Result<Comment>> =
+            serde_json::from_str::<Vec<Comment>>(include_str!("mocked/comments.json"))
+                .unwrap()
+                .into_iter()
+                .map(Ok)
+                .collect();
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact(
+                    "/comments.json?limit=320&group_by=comment&search%5Border%5D=id_desc&page=1"
+                        .into(),
+                ),
+            )
+            .with_body(include_str!("mocked/comments.json"))
+            .create(),
+            // have the next page be empty to end the stream
+            mock(
+                "GET",
+                Matcher::Exact(
+                    "/comments.json?limit=320&group_by=comment&search%5Border%5D=id_desc&page=2"
+                        .into(),
+                ),
+            )
+            .with_body(r#"{"comments":[]}"#)
+            .create(),
+        ];
+
+        assert_eq!(client.recent_comments().collect::<Vec<_>>().await, expected);
+    }
+}
@@ -0,0 +1,90 @@
+use {
+    chrono::{offset::Utc, DateTime, NaiveDateTime, TimeZone},
+    serde::{de::Error as _, Deserialize, Deserializer},
+};
+
+/// Parses a timestamp as sent by the server. Besides RFC 3339 (with or without fractional seconds,
+/// with `Z` or an offset), offsets without a colon are accepted, and timestamps without an offset
+/// are taken to be in UTC.
+fn parse(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    if let Ok(dt) = DateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f%z") {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|dt| Utc.from_utc_datetime(&dt))
+}
+
+/// Deserializes a timestamp, see [`parse`] for the accepted formats.
+pub(crate) fn deserialize<'de, D>(de: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(de)?;
+    parse(&s).ok_or_else(|| D::Error::custom(format!("invalid timestamp: {:?}", s)))
+}
+
+/// Deserializes an optional timestamp, see [`parse`] for the accepted formats. Use it along with
+/// `#[serde(default)]`, so that a missing field is `None` too.
+pub(crate) fn deserialize_option<'de, D>(de: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(de)? {
+        Some(s) => parse(&s)
+            .map(Some)
+            .ok_or_else(|| D::Error::custom(format!("invalid timestamp: {:?}", s))),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_timestamp_shapes() {
+        let expected = Utc.with_ymd_and_hms(2024, 10, 12, 21, 22, 13).unwrap();
+        let millis = chrono::Duration::milliseconds(554);
+
+        assert_eq!(
+            parse("2024-10-12T17:22:13.554-04:00"),
+            Some(expected + millis)
+        );
+        assert_eq!(parse("2024-10-12T17:22:13-04:00"), Some(expected));
+        assert_eq!(parse("2024-10-12T21:22:13.554Z"), Some(expected + millis));
+        assert_eq!(parse("2024-10-12T21:22:13Z"), Some(expected));
+        assert_eq!(
+            parse("2024-10-12T17:22:13.554-0400"),
+            Some(expected + millis)
+        );
+        assert_eq!(parse("2024-10-12T21:22:13.554"), Some(expected + millis));
+        assert_eq!(parse("2024-10-12T21:22:13"), Some(expected));
+        assert_eq!(parse("yesterday"), None);
+    }
+
+    #[test]
+    fn deserialize_option_null() {
+        #[derive(Deserialize)]
+        struct Dates {
+            #[serde(default, deserialize_with = "deserialize_option")]
+            a: Option<DateTime<Utc>>,
+            #[serde(default, deserialize_with = "deserialize_option")]
+            b: Option<DateTime<Utc>>,
+            #[serde(default, deserialize_with = "deserialize_option")]
+            c: Option<DateTime<Utc>>,
+        }
+
+        let dates: Dates =
+            serde_json::from_str(r#"{"a":"2024-10-12T21:22:13Z","b":null}"#).unwrap();
+
+        assert!(dates.a.is_some());
+        assert_eq!(dates.b, None);
+        assert_eq!(dates.c, None);
+    }
+}
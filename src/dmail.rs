@@ -0,0 +1,138 @@
+use {
+    super::{
+        client::{Client, PageStream},
+        error::Result as Rs621Result,
+    },
+    chrono::{offset::Utc, DateTime},
+    futures::{
+        future::{self, Either},
+        prelude::*,
+    },
+    serde::Deserialize,
+};
+
+/// Structure representing a direct message.
+#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[non_exhaustive]
+pub struct Dmail {
+    pub id: u64,
+    pub owner_id: u64,
+    pub from_id: u64,
+    pub to_id: u64,
+    pub title: String,
+    pub body: String,
+    pub is_read: bool,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Client {
+    /// Returns a Stream over the direct messages of the logged in user, newest first. The stream
+    /// yields [`Error::Unauthorized`] if the client isn't logged in.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let mut client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// client.login(("username", "api_key"));
+    ///
+    /// let mut dmail_stream = client.dmails();
+    ///
+    /// while let Some(dmail) = dmail_stream.next().await {
+    ///     println!("{}", dmail?.title);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [`Error::Unauthorized`]: ../error/enum.Error.html#variant.Unauthorized
+    pub fn dmails(&self) -> impl Stream<Item = Rs621Result<Dmail>> + '_ {
+        match self.require_login() {
+            Ok(()) => Either::Left(PageStream::new(self, "/dmails.json".into())),
+            Err(e) => Either::Right(stream::once(future::ready(Err(e)))),
+        }
+    }
+
+    /// Mark a [`Dmail`] (identified by `id`) as read.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let mut client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// client.login(("username", "api_key"));
+    ///
+    /// client.mark_dmail_read(1234).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn mark_dmail_read(&self, id: u64) -> Rs621Result<()> {
+        self.require_login()?;
+        self.put(&format!("/dmails/{id}/mark_as_read.json")).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn dmails() {
+        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        client.login(("foo", "bar"));
+
+        let expected: Vec<Rs621Result<Dmail>> =
+            serde_json::from_str::<Vec<Dmail>>(include_str!("mocked/dmails.json"))
+                .unwrap()
+                .into_iter()
+                .map(Ok)
+                .collect();
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact("/dmails.json?page=1&login=foo&api_key=bar".into()),
+            )
+            .with_body(include_str!("mocked/dmails.json"))
+            .create(),
+            // have the next page be empty to end the stream
+            mock(
+                "GET",
+                Matcher::Exact("/dmails.json?page=2&login=foo&api_key=bar".into()),
+            )
+            .with_body(r#"{"dmails":[]}"#)
+            .create(),
+        ];
+
+        assert_eq!(client.dmails().collect::<Vec<_>>().await, expected);
+    }
+
+    #[tokio::test]
+    async fn dmails_requires_login() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        assert_eq!(
+            client.dmails().collect::<Vec<_>>().await,
+            vec![Err(Error::Unauthorized)]
+        );
+        assert_eq!(client.mark_dmail_read(1234).await, Err(Error::Unauthorized));
+    }
+
+    #[tokio::test]
+    async fn mark_dmail_read() {
+        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        client.login(("foo", "bar"));
+
+        let _m = mock(
+            "POST",
+            Matcher::Exact("/dmails/1234/mark_as_read.json?login=foo&api_key=bar".into()),
+        )
+        .match_body("_method=put")
+        .create();
+
+        client.mark_dmail_read(1234).await.unwrap();
+    }
+}
@@ -0,0 +1,155 @@
+use {
+    super::{
+        client::{send_error, Client},
+        error::{Error, Result as Rs621Result},
+        post::{Post, Query},
+    },
+    futures::prelude::*,
+    std::path::{Path, PathBuf},
+    tokio::{fs, io::AsyncWriteExt},
+};
+
+impl Client {
+    /// Downloads the file of every post matching the search query to `dst_dir`, with at most
+    /// `concurrency` downloads at once. The stream yields each post along with the path of its
+    /// file, named after [`Post::file_name`], in search order.
+    ///
+    /// Deleted posts, which have no file, are skipped. Files are downloaded from the CDN without
+    /// going through the rate limit, but the API requests made to search the posts still do. A
+    /// file is only written to its final path once it's entirely downloaded.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    /// use std::path::Path;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut downloads = client
+    ///     .download_search(&["fluffy"][..], Path::new("downloads"), 4)
+    ///     .take(20);
+    ///
+    /// while let Some(res) = downloads.next().await {
+    ///     let (post, path) = res?;
+    ///     println!("Post #{} saved to {}", post.id, path.display());
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn download_search<'a, T: Into<Query>>(
+        &'a self,
+        tags: T,
+        dst_dir: &'a Path,
+        concurrency: usize,
+    ) -> impl Stream<Item = Rs621Result<(Post, PathBuf)>> + 'a {
+        self.post_search(tags)
+            .try_filter(|post| future::ready(post.file_name().is_some()))
+            .map(move |res| async move {
+                let post = res?;
+                let path = self.download_post_file(&post, dst_dir).await?;
+                Ok((post, path))
+            })
+            .buffered(concurrency.max(1))
+    }
+
+    async fn download_post_file(&self, post: &Post, dst_dir: &Path) -> Rs621Result<PathBuf> {
+        let (url, file_name) = match (&post.file.url, post.file_name()) {
+            (Some(url), Some(file_name)) => (url, file_name),
+            _ => return Err(Error::Serial(format!("post #{} has no file", post.id))),
+        };
+
+        let path = dst_dir.join(&file_name);
+        let part_path = dst_dir.join(format!("{}.part", file_name));
+
+        let mut res = self
+            .client
+            .get(url)
+            .headers(self.headers.clone())
+            .send()
+            .await
+            .map_err(send_error)?;
+
+        if !res.status().is_success() {
+            return Err(Error::Http {
+                url: res.url().clone(),
+                code: res.status().as_u16(),
+                reason: None,
+            });
+        }
+
+        let io_error = |e: std::io::Error| Error::Io(format!("{}", e));
+
+        let mut file = fs::File::create(&part_path).await.map_err(io_error)?;
+
+        while let Some(chunk) = res.chunk().await.map_err(send_error)? {
+            file.write_all(&chunk).await.map_err(io_error)?;
+        }
+
+        file.flush().await.map_err(io_error)?;
+        drop(file);
+
+        fs::rename(&part_path, &path).await.map_err(io_error)?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn download_search() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let dst_dir = std::env::temp_dir().join("rs621_download_search");
+        std::fs::create_dir_all(&dst_dir).unwrap();
+
+        // a post whose file is served by the mock server, and a deleted one
+        let mut post: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/id_8595.json")).unwrap();
+        let mut post = post["post"].take();
+        post["file"]["url"] = format!(
+            "{}/data/e9/fb/e9fbd2f2d0703a9775f245d55b9a0f9f.jpg",
+            mockito::server_url()
+        )
+        .into();
+        let mut deleted = post.clone();
+        deleted["id"] = 8594.into();
+        deleted["file"]["url"] = serde_json::Value::Null;
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact("/posts.json?limit=320&page=1&tags=fluffy".into()),
+            )
+            .with_body(serde_json::json!({ "posts": [post, deleted] }).to_string())
+            .create(),
+            mock(
+                "GET",
+                Matcher::Exact("/posts.json?limit=320&page=b8594&tags=fluffy".into()),
+            )
+            .with_body(r#"{"posts":[]}"#)
+            .create(),
+            mock("GET", "/data/e9/fb/e9fbd2f2d0703a9775f245d55b9a0f9f.jpg")
+                .with_body("not really a jpeg")
+                .create(),
+        ];
+
+        let downloads = client
+            .download_search(&["fluffy"][..], &dst_dir, 2)
+            .collect::<Vec<_>>()
+            .await;
+
+        let path = dst_dir.join("e9fbd2f2d0703a9775f245d55b9a0f9f.jpg");
+
+        assert_eq!(
+            downloads,
+            vec![Ok((serde_json::from_value(post).unwrap(), path.clone()))]
+        );
+        assert_eq!(std::fs::read(&path).unwrap(), b"not really a jpeg");
+
+        std::fs::remove_dir_all(&dst_dir).unwrap();
+    }
+}
@@ -0,0 +1,172 @@
+/// Tags removed by [`strip_dtext`], along with their closing counterpart.
+const TAGS: &[&str] = &[
+    "b", "i", "u", "s", "o", "sup", "sub", "spoiler", "quote", "code", "color", "section", "table",
+    "thead", "tbody", "tr", "th", "td", "ltr", "nodtext",
+];
+
+/// Turns DText, the markup of descriptions, comments and wiki pages (see
+/// <https://e621.net/help/dtext>), into plain text for display: formatting tags, link syntax
+/// and header markers are removed, keeping the text they apply to. Links to wiki pages and
+/// searches keep their label, and collapsible sections keep their title on a line of its own.
+///
+/// ```
+/// # use rs621::dtext::strip_dtext;
+/// let dtext = "h4. Info\n[b]Art[/b] by [[artist_name|the artist]], \"here\":https://example.com";
+/// assert_eq!(strip_dtext(dtext), "Info\nArt by the artist, here");
+/// ```
+pub fn strip_dtext(dtext: &str) -> String {
+    dtext
+        .lines()
+        .map(|line| strip_line(strip_header(line)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Removes the `h1.` to `h6.` marker at the start of a line.
+fn strip_header(line: &str) -> &str {
+    let bytes = line.as_bytes();
+
+    if bytes.len() >= 3 && bytes[0] == b'h' && (b'1'..=b'6').contains(&bytes[1]) && bytes[2] == b'.'
+    {
+        line[3..].trim_start()
+    } else {
+        line
+    }
+}
+
+fn strip_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some((title, after)) = tag(rest) {
+            if !title.is_empty() {
+                out.push_str(title);
+                out.push('\n');
+            }
+
+            rest = after;
+        } else if let Some((text, after)) = wiki_link(rest)
+            .or_else(|| search_link(rest))
+            .or_else(|| url_link(rest))
+        {
+            out.push_str(text);
+            rest = after;
+        } else {
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    out
+}
+
+/// `[[page]]` or `[[page|label]]`.
+fn wiki_link(s: &str) -> Option<(&str, &str)> {
+    let inner = s.strip_prefix("[[")?;
+    let end = inner.find("]]")?;
+    let link = &inner[..end];
+    let label = link.rsplit('|').next().unwrap_or(link);
+
+    Some((label, &inner[end + 2..]))
+}
+
+/// `{{tags}}`.
+fn search_link(s: &str) -> Option<(&str, &str)> {
+    let inner = s.strip_prefix("{{")?;
+    let end = inner.find("}}")?;
+
+    Some((&inner[..end], &inner[end + 2..]))
+}
+
+/// A formatting tag, e.g. `[b]`, `[/b]` or `[color=red]`, along with the title of the section if
+/// it opens one.
+fn tag(s: &str) -> Option<(&str, &str)> {
+    let inner = s.strip_prefix('[')?;
+    let end = inner.find(']')?;
+    let content = &inner[..end];
+    let name = content
+        .trim_start_matches('/')
+        .split(['=', ','])
+        .next()
+        .unwrap_or_default();
+
+    if !TAGS.iter().any(|tag| tag.eq_ignore_ascii_case(name)) {
+        return None;
+    }
+
+    let title = match content.split_once('=') {
+        Some((_, title)) if name.eq_ignore_ascii_case("section") => title,
+        _ => "",
+    };
+
+    Some((title, &inner[end + 1..]))
+}
+
+/// `"label":url` or `"label":[url]`.
+fn url_link(s: &str) -> Option<(&str, &str)> {
+    let inner = s.strip_prefix('"')?;
+    // labels can't contain quotes, so the link must start right after the first closing one
+    let end = inner.find('"')?;
+    let label = &inner[..end];
+    let url = inner[end + 1..].strip_prefix(':')?;
+
+    let after = if let Some(url) = url.strip_prefix('[') {
+        &url[url.find(']')? + 1..]
+    } else if url.starts_with("http") || url.starts_with('/') || url.starts_with('#') {
+        &url[url.find(char::is_whitespace).unwrap_or(url.len())..]
+    } else {
+        return None;
+    };
+
+    Some((label, after))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_formatting() {
+        assert_eq!(
+            strip_dtext("[b]bold[/b], [I]italic[/I] and [color=#ff0000]red[/color]"),
+            "bold, italic and red"
+        );
+        assert_eq!(
+            strip_dtext("[quote]\nfoo said:\nhi\n[/quote]\nhello"),
+            "\nfoo said:\nhi\n\nhello"
+        );
+    }
+
+    #[test]
+    fn strip_links() {
+        assert_eq!(
+            strip_dtext("see [[fluffy]], [[fluffy_tail|tails]] and {{fluffy rating:s}}"),
+            "see fluffy, tails and fluffy rating:s"
+        );
+        assert_eq!(
+            strip_dtext("\"my gallery\":https://example.com/a?b=c and \"FAQ\":[/help/faq]!"),
+            "my gallery and FAQ!"
+        );
+        assert_eq!(
+            strip_dtext("He said \"hi\" and \"link\":http://x"),
+            "He said \"hi\" and link"
+        );
+    }
+
+    #[test]
+    fn strip_headers_and_sections() {
+        assert_eq!(
+            strip_dtext("h2. Links\n[section=Sketches]post #1234[/section]"),
+            "Links\nSketches\npost #1234"
+        );
+    }
+
+    #[test]
+    fn keep_plain_text() {
+        assert_eq!(
+            strip_dtext("5 [not a tag] \"quoted\": text, h1 no header"),
+            "5 [not a tag] \"quoted\": text, h1 no header"
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use url::Url;
 
 #[derive(Debug, thiserror::Error, PartialEq)]
@@ -9,9 +10,17 @@ pub enum Error {
         reason: Option<String>,
     },
 
+    #[error("Validation failed: {}", format_validation_errors(.errors))]
+    Validation {
+        errors: HashMap<String, Vec<String>>,
+    },
+
     #[error("Serialization error: {0}")]
     Serial(String),
 
+    #[error("I/O error: {0}")]
+    Io(String),
+
     #[error("Couldn't send request: {0}")]
     CannotSendRequest(String),
 
@@ -21,10 +30,52 @@ pub enum Error {
     #[error("Invalid header value: {0}")]
     InvalidHeaderValue(String),
 
+    #[error("Too many tags in search query: {count} (the limit is {limit})")]
+    TooManyTags { count: usize, limit: usize },
+
+    #[error("The parents of post #{id} loop or go too deep")]
+    ParentCycle { id: u64 },
+
+    #[error("Post #{post_id} is invalid: {reason}")]
+    Invalid { post_id: u64, reason: String },
+
+    #[error("Post #{post_id} of pool #{pool_id} couldn't be found")]
+    MissingPoolPost { pool_id: u64, post_id: u64 },
+
+    #[error("Response body exceeds the limit of {limit} bytes")]
+    ResponseTooLarge { limit: usize },
+
+    #[error("The request timed out")]
+    Timeout,
+
+    #[error("Incomplete credentials: {missing} isn't set")]
+    IncompleteCredentials { missing: String },
+
+    #[error("This operation requires to be logged in")]
+    Unauthorized,
+
+    #[error("The client was shut down")]
+    Shutdown,
+
+    #[error("The resource wasn't modified")]
+    NotModified,
+
     #[error("Malformed URL: {0}")]
     UrlParse(#[from] url::ParseError),
 }
 
+/// Formats validation errors as `field: message` pairs, sorted by field for a stable output.
+fn format_validation_errors(errors: &HashMap<String, Vec<String>>) -> String {
+    let mut fields: Vec<_> = errors.iter().collect();
+    fields.sort();
+
+    fields
+        .into_iter()
+        .flat_map(|(field, messages)| messages.iter().map(move |m| format!("{}: {}", field, m)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Result type for `rs621`, using [`rs621::error::Error`].
 ///
 /// [`rs621::error::Error`]: enum.Error.html
@@ -0,0 +1,100 @@
+use {
+    super::{
+        client::Client,
+        error::{Error, Result as Rs621Result},
+        post::Query,
+    },
+    futures::prelude::*,
+    tokio::io::{AsyncWrite, AsyncWriteExt},
+};
+
+impl Client {
+    /// Writes the posts matching the search query to `out` as newline-delimited JSON (one post
+    /// per line), up to `limit` posts if given, and returns the number of posts written. Posts are
+    /// written as they come, so the whole search is never held in memory.
+    ///
+    /// The export stops at the first error, once everything written until then is flushed.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// // any `AsyncWrite`, e.g. a file or a socket
+    /// let mut out = Vec::new();
+    ///
+    /// let count = client
+    ///     .export_ndjson(&["fluffy"][..], Some(1000), &mut out)
+    ///     .await?;
+    /// println!("Exported {} posts", count);
+    /// # Ok(()) }
+    /// ```
+    pub async fn export_ndjson<T, W>(
+        &self,
+        tags: T,
+        limit: Option<usize>,
+        out: &mut W,
+    ) -> Rs621Result<u64>
+    where
+        T: Into<Query>,
+        W: AsyncWrite + Unpin,
+    {
+        let mut posts = self.post_search(tags).take(limit.unwrap_or(usize::MAX));
+        let mut count = 0;
+
+        let res = async {
+            while let Some(post) = posts.next().await {
+                let mut line =
+                    serde_json::to_vec(&post?).map_err(|e| Error::Serial(format!("{}", e)))?;
+                line.push(b'\n');
+
+                out.write_all(&line)
+                    .await
+                    .map_err(|e| Error::Io(format!("{}", e)))?;
+                count += 1;
+            }
+
+            Ok(())
+        }
+        .await;
+
+        out.flush().await.map_err(|e| Error::Io(format!("{}", e)))?;
+
+        res.map(|()| count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::post::Post;
+    use mockito::mock;
+
+    #[tokio::test]
+    async fn export_ndjson() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock("GET", "/posts.json?limit=320&page=1&tags=fluffy")
+            .with_body(include_str!("mocked/id_8595_535_2105_1470.json"))
+            .create();
+
+        let mut out = Vec::new();
+        let count = client
+            .export_ndjson("fluffy", Some(3), &mut out)
+            .await
+            .unwrap();
+
+        let posts: Vec<Post> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(count, 3);
+        assert_eq!(
+            posts.iter().map(|p| p.id).collect::<Vec<_>>(),
+            vec![8595, 2105, 1470]
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use {
+    super::{
+        client::{query_param, Client, PageStream},
+        error::Result as Rs621Result,
+    },
+    chrono::{offset::Utc, DateTime},
+    futures::prelude::*,
+    serde::Deserialize,
+};
+
+/// Structure representing a flag, i.e. a request for a post to be deleted.
+#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[non_exhaustive]
+pub struct PostFlag {
+    pub id: u64,
+    /// The flagged post, which can be fetched with [`Client::get_post`].
+    pub post_id: u64,
+    pub creator_id: u64,
+    pub reason: String,
+    pub is_resolved: bool,
+    /// Whether the flag was created by the deletion of the post, rather than by a user.
+    pub is_deletion: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Client {
+    /// Returns a Stream over the flags that haven't been resolved yet, newest first.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut flags = client.pending_flags();
+    ///
+    /// while let Some(flag) = flags.next().await {
+    ///     let flag = flag?;
+    ///     println!("#{}: {}", flag.post_id, flag.reason);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn pending_flags(&self) -> impl Stream<Item = Rs621Result<PostFlag>> + '_ {
+        PageStream::new(
+            self,
+            format!(
+                "/post_flags.json?limit=320&{}",
+                query_param("search[is_resolved]", "false"),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn pending_flags() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let expected: Vec<Rs621Result<PostFlag>> =
+            serde_json::from_str::<Vec<PostFlag>>(include_str!("mocked/post_flags.json"))
+                .unwrap()
+                .into_iter()
+                .map(Ok)
+                .collect();
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact(
+                    "/post_flags.json?limit=320&search%5Bis_resolved%5D=false&page=1".into(),
+                ),
+            )
+            .with_body(include_str!("mocked/post_flags.json"))
+            .create(),
+            // have the next page be empty to end the stream
+            mock(
+                "GET",
+                Matcher::Exact(
+                    "/post_flags.json?limit=320&search%5Bis_resolved%5D=false&page=2".into(),
+                ),
+            )
+            .with_body(r#"{"post_flags":[]}"#)
+            .create(),
+        ];
+
+        assert_eq!(client.pending_flags().collect::<Vec<_>>().await, expected);
+    }
+}
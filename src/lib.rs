@@ -57,6 +57,32 @@
 //! you'd like to fetch, a single call to [`Client::get_posts`] should be enough and WILL be
 //! faster. Do NOT call it repeatedly in a loop.
 //!
+//! Outside of WASM, the streams returned by the client are [`Send`], so they can be moved to
+//! another task (e.g. with `tokio::spawn`) as long as the client outlives them. They aren't
+//! [`Sync`] because the pending requests they hold aren't either, which only matters when sharing
+//! a stream by reference. Clients are cheap to clone, and clones share the rate limit, so the
+//! easiest way to make a client outlive a task is to move a clone into it.
+//!
+//! ## WebAssembly
+//!
+//! `rs621` can be used from a browser, on `wasm32`. Browsers only send cross-origin requests
+//! without asking the server first (a CORS "preflight" request, which e621 doesn't allow) if they
+//! are simple enough, so on WASM the User-Agent and the credentials are sent in the query string
+//! rather than in headers, and every write is a POST request with a form body. Actual DELETE and
+//! PUT requests are emulated with a `_method` form field (unless
+//! [`ClientBuilder::use_http_verbs`] is enabled). This applies to all the methods that modify
+//! something on the site:
+//!
+//! - [`Client::post_favorite`] and [`Client::favorite_posts`]
+//! - [`Client::post_unfavorite`]
+//! - [`Client::post_vote`]
+//! - [`Client::delete_post`]
+//! - [`Client::add_to_set`] and [`Client::remove_from_set`]
+//! - [`Client::mark_dmail_read`]
+//!
+//! [`Client::get_json_endpoint_if_changed`] and [`Client::post_search_if_changed`] send an
+//! `If-None-Match` header when given an ETag, which does need a preflight request.
+//!
 //! ## Notes from the official API:
 //!
 //! ### User Agents
@@ -89,6 +115,17 @@
 //! [`Stream`]: https://docs.rs/futures/0.3.5/futures/stream/trait.Stream.html
 //! [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
 //! [`Client::get_posts`]: client/struct.Client.html#method.get_posts
+//! [`ClientBuilder::use_http_verbs`]: client/struct.ClientBuilder.html#method.use_http_verbs
+//! [`Client::post_favorite`]: client/struct.Client.html#method.post_favorite
+//! [`Client::favorite_posts`]: client/struct.Client.html#method.favorite_posts
+//! [`Client::post_unfavorite`]: client/struct.Client.html#method.post_unfavorite
+//! [`Client::post_vote`]: client/struct.Client.html#method.post_vote
+//! [`Client::delete_post`]: client/struct.Client.html#method.delete_post
+//! [`Client::add_to_set`]: client/struct.Client.html#method.add_to_set
+//! [`Client::remove_from_set`]: client/struct.Client.html#method.remove_from_set
+//! [`Client::mark_dmail_read`]: client/struct.Client.html#method.mark_dmail_read
+//! [`Client::get_json_endpoint_if_changed`]: client/struct.Client.html#method.get_json_endpoint_if_changed
+//! [`Client::post_search_if_changed`]: client/struct.Client.html#method.post_search_if_changed
 
 /// Client related structures.
 pub mod client;
@@ -101,3 +138,44 @@ pub mod post;
 
 /// Pool management.
 pub mod pool;
+
+/// Direct message management.
+pub mod dmail;
+
+/// Tag management.
+pub mod tag;
+
+/// Post set management.
+pub mod set;
+
+/// Comment management.
+pub mod comment;
+
+/// Post approval history.
+pub mod approval;
+
+/// Note management.
+pub mod note;
+
+/// User management.
+pub mod user;
+
+/// Post edit history.
+pub mod post_version;
+
+/// Post flag management.
+pub mod flag;
+
+/// Wiki pages.
+pub mod wiki;
+
+/// DText markup helpers.
+pub mod dtext;
+
+#[cfg(all(feature = "download", not(target_family = "wasm")))]
+mod download;
+
+#[cfg(feature = "export")]
+mod export;
+
+mod datetime;
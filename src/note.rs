@@ -0,0 +1,158 @@
+use {
+    super::{
+        client::{query_param, Client, PageStream},
+        error::Result as Rs621Result,
+        post::PostFile,
+    },
+    chrono::{offset::Utc, DateTime},
+    futures::prelude::*,
+    serde::Deserialize,
+};
+
+/// Structure representing a note, i.e. a box of text overlaid on a region of a post's image.
+#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[non_exhaustive]
+pub struct Note {
+    pub id: u64,
+    pub post_id: u64,
+    pub creator_id: u64,
+    pub creator_name: String,
+    /// Position and size of the region, in pixels of the original file.
+    pub x: u64,
+    pub y: u64,
+    pub width: u64,
+    pub height: u64,
+    pub body: String,
+    pub version: u64,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Note {
+    /// Returns the `(x, y, width, height)` of the note's region on the image displayed at
+    /// `display_width` by `display_height`, e.g. the sample or the preview. `file` is the post's
+    /// original file, which the note's coordinates are relative to.
+    ///
+    /// ```
+    /// # use rs621::{note::Note, post::PostFile};
+    /// // with a 1600x1200 file and a note at (400, 300), 200x100
+    /// # let file: PostFile = serde_json::from_value(serde_json::json!({
+    /// #     "width": 1600, "height": 1200, "ext": "png", "size": 1, "md5": "", "url": null,
+    /// # })).unwrap();
+    /// # let note: Note = serde_json::from_value(serde_json::json!({
+    /// #     "id": 1, "post_id": 8595, "creator_id": 1, "creator_name": "foo",
+    /// #     "x": 400, "y": 300, "width": 200, "height": 100, "body": "hi",
+    /// #     "version": 1, "is_active": true,
+    /// #     "created_at": "2020-01-01T00:00:00Z", "updated_at": "2020-01-01T00:00:00Z",
+    /// # })).unwrap();
+    /// assert_eq!(note.scaled(800, 600, &file), (200.0, 150.0, 100.0, 50.0));
+    /// ```
+    pub fn scaled(
+        &self,
+        display_width: u64,
+        display_height: u64,
+        file: &PostFile,
+    ) -> (f64, f64, f64, f64) {
+        let scale_x = display_width as f64 / file.width as f64;
+        let scale_y = display_height as f64 / file.height as f64;
+
+        (
+            self.x as f64 * scale_x,
+            self.y as f64 * scale_y,
+            self.width as f64 * scale_x,
+            self.height as f64 * scale_y,
+        )
+    }
+}
+
+impl Client {
+    /// Returns a Stream over the notes of the post identified by `post_id`.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut notes = client.post_notes(8595);
+    ///
+    /// while let Some(note) = notes.next().await {
+    ///     println!("{}", note?.body);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn post_notes(&self, post_id: u64) -> impl Stream<Item = Rs621Result<Note>> + '_ {
+        PageStream::new(
+            self,
+            format!(
+                "/notes.json?limit=320&{}",
+                query_param("search[post_id]", &post_id.to_string()),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::post::PostFileExtension;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn post_notes() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let expected: Vec<Rs621Result<Note>> =
+            serde_json::from_str::<Vec<Note>>(include_str!("mocked/notes_8595.json"))
+                .unwrap()
+                .into_iter()
+                .map(Ok)
+                .collect();
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact("/notes.json?limit=320&search%5Bpost_id%5D=8595&page=1".into()),
+            )
+            .with_body(include_str!("mocked/notes_8595.json"))
+            .create(),
+            // have the next page be empty to end the stream
+            mock(
+                "GET",
+                Matcher::Exact("/notes.json?limit=320&search%5Bpost_id%5D=8595&page=2".into()),
+            )
+            .with_body("[]")
+            .create(),
+        ];
+
+        assert_eq!(client.post_notes(8595).collect::<Vec<_>>().await, expected);
+    }
+
+    #[test]
+    fn note_scaled() {
+        let note: Note = serde_json::from_str::<Vec<Note>>(include_str!("mocked/notes_8595.json"))
+            .unwrap()
+            .remove(0);
+
+        let file = PostFile {
+            width: 800,
+            height: 616,
+            ext: PostFileExtension::Jpeg,
+            size: 135618,
+            md5: String::from("e9fbd2f2d0703a9775f245d55b9a0f9f"),
+            url: None,
+        };
+
+        // same size as the file
+        assert_eq!(note.scaled(800, 616, &file), (400.0, 108.0, 200.0, 77.0));
+
+        // the preview of #8595 is 150x115, the ratio is slightly different on each axis
+        let (x, y, width, height) = note.scaled(150, 115, &file);
+        assert_eq!((x, width), (75.0, 37.5));
+        assert!((y - 108.0 * 115.0 / 616.0).abs() < 1e-9);
+        assert!((height - 77.0 * 115.0 / 616.0).abs() < 1e-9);
+    }
+}
@@ -2,8 +2,9 @@ use crate::error::Error;
 
 use {
     super::{
-        client::{Client, QueryFuture},
+        client::{endpoint_with_query, Client, QueryFuture},
         error::Result as Rs621Result,
+        post::{Post, PostSearchStream, Query, SearchPage},
     },
     chrono::{offset::Utc, DateTime},
     derivative::Derivative,
@@ -12,11 +13,17 @@ use {
         task::{Context, Poll},
     },
     itertools::Itertools,
-    serde::Deserialize,
-    std::pin::Pin,
+    serde::{Deserialize, Serialize, Serializer},
+    std::{collections::HashMap, pin::Pin},
 };
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+/// Maximum number of pool IDs looked up in a single request.
+const POOL_IDS_CHUNK_SIZE: usize = 100;
+
+/// Number of posts fetched at once by [`Client::pool_posts`].
+const POOL_POSTS_CHUNK_SIZE: usize = 100;
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PoolCategory {
     Series,
@@ -25,10 +32,13 @@ pub enum PoolCategory {
 
 /// Structure representing a pool.
 #[derive(Debug, PartialEq, Eq, Deserialize)]
+#[non_exhaustive]
 pub struct Pool {
     pub id: u64,
     pub name: String,
+    #[serde(deserialize_with = "crate::datetime::deserialize")]
     pub created_at: DateTime<Utc>,
+    #[serde(deserialize_with = "crate::datetime::deserialize")]
     pub updated_at: DateTime<Utc>,
     pub creator_id: u64,
     pub description: String,
@@ -40,7 +50,52 @@ pub struct Pool {
     pub post_count: u64,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+impl Pool {
+    /// Returns the index of the post in the pool, starting at 0, along with the number of posts
+    /// in the pool. Returns `None` if the post isn't in the pool.
+    ///
+    /// ```no_run
+    /// # use rs621::pool::Pool;
+    /// # fn f(pool: Pool) {
+    /// if let Some((index, total)) = pool.position_of(8595) {
+    ///     println!("Page {} of {}", index + 1, total);
+    /// }
+    /// # }
+    /// ```
+    pub fn position_of(&self, post_id: u64) -> Option<(usize, usize)> {
+        self.post_ids
+            .iter()
+            .position(|&id| id == post_id)
+            .map(|index| (index, self.post_ids.len()))
+    }
+
+    /// Returns the ID of the post after `current` in the pool, or `None` if `current` is the
+    /// last post or isn't in the pool.
+    pub fn next_post(&self, current: u64) -> Option<u64> {
+        let (index, _) = self.position_of(current)?;
+        self.post_ids.get(index + 1).copied()
+    }
+
+    /// Returns the ID of the post before `current` in the pool, or `None` if `current` is the
+    /// first post or isn't in the pool.
+    pub fn prev_post(&self, current: u64) -> Option<u64> {
+        let (index, _) = self.position_of(current)?;
+        index.checked_sub(1).map(|index| self.post_ids[index])
+    }
+}
+
+/// How [`Client::pool_posts`] handles posts of the pool that can't be fetched, e.g. because they
+/// were removed from the pool or deleted after the pool itself was fetched.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PoolPostsMode {
+    /// Yield [`Error::MissingPoolPost`] in place of the missing posts.
+    Strict,
+    /// Skip the missing posts.
+    Lenient,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PoolSearchOrder {
     Name,
     CreatedAt,
@@ -48,97 +103,55 @@ pub enum PoolSearchOrder {
     PostCount,
 }
 
-#[derive(Debug, PartialEq, Eq, Default)]
+/// Parameters of a pool search. Serializes to the query parameters of the `/pools.json`
+/// endpoint.
+#[derive(Debug, PartialEq, Eq, Default, Serialize)]
 pub struct PoolSearch {
+    #[serde(
+        rename = "search[name_matches]",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub name_matches: Option<String>,
+    #[serde(
+        rename = "search[id]",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_ids"
+    )]
     pub id: Option<Vec<u64>>,
+    #[serde(
+        rename = "search[description_matches]",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub description_matches: Option<String>,
+    #[serde(
+        rename = "search[creator_name]",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub creator_name: Option<String>,
+    #[serde(rename = "search[creator_id]", skip_serializing_if = "Option::is_none")]
     pub creator_id: Option<u64>,
+    #[serde(rename = "search[is_active]", skip_serializing_if = "Option::is_none")]
     pub is_active: Option<bool>,
+    #[serde(rename = "search[is_deleted]", skip_serializing_if = "Option::is_none")]
     pub is_deleted: Option<bool>,
+    #[serde(rename = "search[category]", skip_serializing_if = "Option::is_none")]
     pub category: Option<PoolCategory>,
+    #[serde(rename = "search[order]", skip_serializing_if = "Option::is_none")]
     pub order: Option<PoolSearchOrder>,
+    /// Number of pools fetched per request.
+    #[serde(rename = "limit", skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<u16>,
 }
 
-impl PoolSearch {
-    fn to_search_parameters(&self) -> String {
-        let mut params = String::new();
-
-        if let Some(ref value) = self.name_matches {
-            params.push('&');
-            params.push_str(&urlencoding::encode("search[name_matches]"));
-            params.push_str("=");
-            params.push_str(&urlencoding::encode(&value));
-        }
-
-        if let Some(ref value) = self.id {
-            params.push('&');
-            params.push_str(&urlencoding::encode("search[id]"));
-            params.push_str("=");
-            params.push_str(&urlencoding::encode(&value.iter().join(",")));
-        }
-
-        if let Some(ref value) = self.description_matches {
-            params.push('&');
-            params.push_str(&urlencoding::encode("search[description_matches]"));
-            params.push_str("=");
-            params.push_str(&urlencoding::encode(&value));
-        }
-
-        if let Some(ref value) = self.creator_name {
-            params.push('&');
-            params.push_str(&urlencoding::encode("search[creator_name]"));
-            params.push_str("=");
-            params.push_str(&urlencoding::encode(&value));
-        }
-
-        if let Some(ref value) = self.creator_id {
-            params.push('&');
-            params.push_str(&urlencoding::encode("search[creator_id]"));
-            params.push_str("=");
-            params.push_str(&urlencoding::encode(&value.to_string()));
-        }
-
-        if let Some(ref value) = self.is_active {
-            params.push('&');
-            params.push_str(&urlencoding::encode("search[is_active]"));
-            params.push_str("=");
-            params.push_str(&urlencoding::encode(&value.to_string()));
-        }
-
-        if let Some(ref value) = self.is_deleted {
-            params.push('&');
-            params.push_str(&urlencoding::encode("search[is_deleted]"));
-            params.push_str("=");
-            params.push_str(&urlencoding::encode(&value.to_string()));
-        }
-
-        if let Some(ref value) = self.category {
-            params.push('&');
-            params.push_str(&urlencoding::encode("search[category]"));
-            params.push_str("=");
-            params.push_str(&urlencoding::encode(match value {
-                PoolCategory::Series => "series",
-                PoolCategory::Collection => "collection",
-            }));
-        }
-
-        if let Some(ref value) = self.order {
-            params.push('&');
-            params.push_str(&urlencoding::encode("search[order]"));
-            params.push_str("=");
-            params.push_str(&urlencoding::encode(match value {
-                PoolSearchOrder::Name => "name",
-                PoolSearchOrder::CreatedAt => "created_at",
-                PoolSearchOrder::UpdatedAt => "updated_at",
-                PoolSearchOrder::PostCount => "post_count",
-            }));
-        }
-
-        params
+/// Serializes a list of IDs the way the API expects them, separated by commas.
+fn serialize_ids<S: Serializer>(ids: &Option<Vec<u64>>, s: S) -> Result<S::Ok, S::Error> {
+    match ids {
+        Some(ids) => s.serialize_str(&ids.iter().join(",")),
+        None => s.serialize_none(),
     }
+}
 
+impl PoolSearch {
     pub fn new() -> Self {
         PoolSearch::default()
     }
@@ -187,6 +200,11 @@ impl PoolSearch {
         self.order = Some(value);
         self
     }
+
+    pub fn per_page(mut self, value: u16) -> Self {
+        self.per_page = Some(value);
+        self
+    }
 }
 
 type PoolSearchApiResponse = Vec<Pool>;
@@ -202,13 +220,13 @@ pub struct PoolStream<'a> {
     #[derivative(Debug = "ignore")]
     query_future: Option<Pin<QueryFuture>>,
 
-    page: u64,
+    next_page: SearchPage,
     chunk: Vec<Rs621Result<Pool>>,
     ended: bool,
 }
 
 impl<'a> PoolStream<'a> {
-    fn new(client: &'a Client, search: PoolSearch) -> Self {
+    fn new(client: &'a Client, search: PoolSearch, page: SearchPage) -> Self {
         PoolStream {
             client,
             search,
@@ -216,7 +234,7 @@ impl<'a> PoolStream<'a> {
             query_url: None,
             query_future: None,
 
-            page: 1,
+            next_page: page,
             chunk: Vec::new(),
             ended: false,
         }
@@ -254,11 +272,26 @@ impl<'a> Stream for PoolStream<'a> {
                                         Err(e) => vec![Err(Error::Serial(format!("{}", e)))],
                                     };
 
+                                let last_id = match this.chunk.first() {
+                                    Some(Ok(pool)) => pool.id,
+                                    _ => 0,
+                                };
+
+                                // we now know what will be the next page
+                                this.next_page = match this.next_page {
+                                    SearchPage::Page(i) => SearchPage::Page(i + 1),
+                                    SearchPage::BeforePost(_) => SearchPage::BeforePost(last_id),
+                                    SearchPage::AfterPost(_) => SearchPage::AfterPost(last_id),
+                                };
+
                                 // mark the stream as ended if there was no pools
                                 this.ended = this.chunk.is_empty();
                                 QueryPollRes::NotFetching
                             }
 
+                            // a timed out page is fetched again if the stream is polled further
+                            Err(Error::Timeout) => QueryPollRes::Err(Error::Timeout),
+
                             // if there was an error, stream it and mark the stream as ended
                             Err(e) => {
                                 this.ended = true;
@@ -292,21 +325,25 @@ impl<'a> Stream for PoolStream<'a> {
                 QueryPollRes::NotFetching => {
                     // we need to load a new chunk of pools
                     let url = format!(
-                        "/pools.json?page={}{}",
-                        {
-                            let page = this.page;
-                            this.page += 1;
-                            page
+                        "/pools.json?page={}",
+                        match this.next_page {
+                            SearchPage::Page(i) => format!("{}", i),
+                            SearchPage::BeforePost(i) => format!("b{}", i),
+                            SearchPage::AfterPost(i) => format!("a{}", i),
                         },
-                        this.search.to_search_parameters(),
                     );
-                    this.query_url = Some(url);
+
+                    match endpoint_with_query(&url, &this.search) {
+                        Ok(url) => this.query_url = Some(url),
+                        Err(e) => {
+                            this.ended = true;
+                            return Poll::Ready(Some(Err(e)));
+                        }
+                    }
 
                     // get the JSON
-                    this.query_future = Some(Box::pin(
-                        this.client
-                            .get_json_endpoint(this.query_url.as_ref().unwrap()),
-                    ));
+                    this.query_future =
+                        Some(this.client.get_json_page(this.query_url.as_ref().unwrap()));
                 }
             }
         }
@@ -332,7 +369,273 @@ impl Client {
     /// # Ok(()) }
     /// ```
     pub fn pool_search<'a>(&'a self, search: PoolSearch) -> PoolStream<'a> {
-        PoolStream::new(self, search)
+        self.pool_search_from_page(search, SearchPage::Page(1))
+    }
+
+    /// Performs a pool search, starting from the given page. [`SearchPage::BeforePost`] and
+    /// [`SearchPage::AfterPost`] hold a pool ID here, and should only be used with the default
+    /// order (by ID).
+    ///
+    /// ```no_run
+    /// # use rs621::{client::Client, pool::PoolSearch, post::SearchPage};
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut pool_stream = client.pool_search_from_page(
+    ///     PoolSearch::new().name_matches("foo").per_page(100),
+    ///     SearchPage::BeforePost(12345),
+    /// );
+    ///
+    /// while let Some(pool) = pool_stream.next().await {
+    ///     assert!(pool?.id < 12345);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn pool_search_from_page<'a>(
+        &'a self,
+        search: PoolSearch,
+        page: SearchPage,
+    ) -> PoolStream<'a> {
+        PoolStream::new(self, search, page)
+    }
+
+    /// Returns a Stream over the pools the post with the given ID is in.
+    ///
+    /// The API can't search pools by the posts they contain, so this is done in two steps: the
+    /// post is fetched first, then the pools listed in [`Post::pools`] are looked up, up to 100
+    /// per request. Pools that can't be found (e.g. deleted ones) are skipped.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut pools = client.pools_containing(8595);
+    ///
+    /// while let Some(pool) = pools.next().await {
+    ///     println!("- {}", pool?.name);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn pools_containing(
+        &self,
+        post_id: u64,
+    ) -> impl Stream<Item = Rs621Result<Pool>> + Unpin + '_ {
+        Box::pin(
+            self.get_post(post_id)
+                .into_stream()
+                .map_ok(move |post| {
+                    let chunks: Vec<Vec<u64>> = post
+                        .pools
+                        .chunks(POOL_IDS_CHUNK_SIZE)
+                        .map(<[u64]>::to_vec)
+                        .collect();
+
+                    stream::iter(chunks)
+                        .map(move |chunk| {
+                            let len = chunk.len();
+                            let search = PoolSearch::new()
+                                .id(chunk)
+                                .per_page(POOL_IDS_CHUNK_SIZE as u16);
+
+                            // the page after the last one is empty, don't bother fetching it
+                            self.pool_search(search).take(len)
+                        })
+                        .flatten()
+                })
+                .try_flatten(),
+        )
+    }
+
+    /// Returns a Stream over the posts of the pool with the given ID, in the order of the pool.
+    ///
+    /// The pool is fetched once, then its posts are fetched 100 at a time. If the pool is edited
+    /// in the meantime, the posts are still those it had when it was fetched, in that order, and
+    /// `mode` tells what to do with the ones that can't be found anymore.
+    ///
+    /// ```no_run
+    /// # use rs621::{client::Client, pool::PoolPostsMode};
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut posts = client.pool_posts(12345, PoolPostsMode::Lenient);
+    ///
+    /// while let Some(post) = posts.next().await {
+    ///     println!("- #{}", post?.id);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn pool_posts(
+        &self,
+        pool_id: u64,
+        mode: PoolPostsMode,
+    ) -> impl Stream<Item = Rs621Result<Post>> + Unpin + '_ {
+        let pool = self
+            .get_json_endpoint(&format!("/pools/{}.json", pool_id))
+            .map(|res| {
+                res.and_then(|body| {
+                    serde_json::from_value::<Pool>(body)
+                        .map_err(|e| Error::Serial(format!("{}", e)))
+                })
+            });
+
+        Box::pin(
+            pool.into_stream()
+                .map_ok(move |pool| {
+                    let chunks: Vec<Vec<u64>> = pool
+                        .post_ids
+                        .chunks(POOL_POSTS_CHUNK_SIZE)
+                        .map(<[u64]>::to_vec)
+                        .collect();
+
+                    stream::iter(chunks)
+                        .then(move |chunk| async move {
+                            let mut found = HashMap::new();
+                            let mut posts = self.get_posts(chunk.clone());
+
+                            while let Some(post) = posts.next().await {
+                                match post {
+                                    Ok(post) => found.insert(post.id, post),
+                                    Err(e) => return vec![Err(e)],
+                                };
+                            }
+
+                            // the posts come back in any order, put them back in the pool's
+                            chunk
+                                .into_iter()
+                                .filter_map(|post_id| match found.remove(&post_id) {
+                                    Some(post) => Some(Ok(post)),
+                                    None if mode == PoolPostsMode::Strict => {
+                                        Some(Err(Error::MissingPoolPost { pool_id, post_id }))
+                                    }
+                                    None => None,
+                                })
+                                .collect()
+                        })
+                        .map(stream::iter)
+                        .flatten()
+                })
+                .try_flatten(),
+        )
+    }
+
+    /// Searches posts and groups them by pool, for comic browsing. Up to `limit` posts are
+    /// fetched, then the pools they're in are looked up.
+    ///
+    /// Returns the pools along with their posts, in the order the pools first appear in the
+    /// results, and the posts that aren't in any pool. Within a pool, posts are sorted by their
+    /// position in the pool. A post in several pools is listed under each of them, and pools that
+    /// can't be found (e.g. deleted ones) are ignored.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let (pools, standalone) = client.search_grouped_by_pool("comic fluffy", 100).await?;
+    ///
+    /// for (pool, posts) in pools {
+    ///     println!("{}: {} posts", pool.name, posts.len());
+    /// }
+    ///
+    /// println!("{} posts outside of pools", standalone.len());
+    /// # Ok(()) }
+    /// ```
+    pub async fn search_grouped_by_pool<T: Into<Query>>(
+        &self,
+        tags: T,
+        limit: usize,
+    ) -> Rs621Result<(Vec<(Pool, Vec<Post>)>, Vec<Post>)> {
+        let posts: Vec<Post> = self.post_search(tags).take(limit).try_collect().await?;
+
+        // every pool, in order of appearance
+        let mut pool_ids: Vec<u64> = Vec::new();
+        for post in &posts {
+            for id in &post.pools {
+                if !pool_ids.contains(id) {
+                    pool_ids.push(*id);
+                }
+            }
+        }
+
+        let mut pools: HashMap<u64, Pool> = HashMap::new();
+        for chunk in pool_ids.chunks(POOL_IDS_CHUNK_SIZE) {
+            let search = PoolSearch::new()
+                .id(chunk.to_vec())
+                .per_page(POOL_IDS_CHUNK_SIZE as u16);
+
+            // the page after the last one is empty, don't bother fetching it
+            let found: Vec<Pool> = self
+                .pool_search(search)
+                .take(chunk.len())
+                .try_collect()
+                .await?;
+
+            pools.extend(found.into_iter().map(|pool| (pool.id, pool)));
+        }
+
+        let mut groups: Vec<(Pool, Vec<Post>)> = Vec::new();
+        for id in pool_ids {
+            if let Some(pool) = pools.remove(&id) {
+                let mut pool_posts: Vec<Post> = posts
+                    .iter()
+                    .filter(|post| post.pools.contains(&id))
+                    .cloned()
+                    .collect();
+
+                pool_posts.sort_by_key(|post| pool.post_ids.iter().position(|&p| p == post.id));
+                groups.push((pool, pool_posts));
+            }
+        }
+
+        let standalone = posts
+            .into_iter()
+            .filter(|post| {
+                !post
+                    .pools
+                    .iter()
+                    .any(|id| groups.iter().any(|(p, _)| p.id == *id))
+            })
+            .collect();
+
+        Ok((groups, standalone))
+    }
+
+    /// Searches the posts of the pool with the given ID that also match `extra_tags`, e.g. to
+    /// only get its safe posts. This is a regular search for `pool:<id>` and the extra tags:
+    /// posts come in the order of the search (newest first, unless an `order:` tag is given),
+    /// not in the order of the pool. Use [`Client::pool_posts`] to read a pool in order.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut posts = client.pool_post_search(12345, &["rating:s"]);
+    ///
+    /// while let Some(post) = posts.next().await {
+    ///     println!("- #{}", post?.id);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn pool_post_search(&self, pool_id: u64, extra_tags: &[&str]) -> PostSearchStream<'_> {
+        let mut tags = vec![format!("pool:{}", pool_id)];
+        tags.extend(extra_tags.iter().map(|&tag| String::from(tag)));
+
+        self.post_search(tags)
     }
 }
 
@@ -341,6 +644,50 @@ mod tests {
     use super::*;
     use mockito::mock;
 
+    #[test]
+    fn pool_navigation() {
+        let mut pool: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/pool_search-foo.json")).unwrap();
+        let mut pool = pool[0].take();
+        pool["post_ids"] = serde_json::json!([30, 10, 20]);
+        let pool: Pool = serde_json::from_value(pool).unwrap();
+
+        assert_eq!(pool.position_of(30), Some((0, 3)));
+        assert_eq!(pool.position_of(20), Some((2, 3)));
+        assert_eq!(pool.position_of(40), None);
+
+        // first post
+        assert_eq!(pool.prev_post(30), None);
+        assert_eq!(pool.next_post(30), Some(10));
+
+        assert_eq!(pool.prev_post(10), Some(30));
+        assert_eq!(pool.next_post(10), Some(20));
+
+        // last post
+        assert_eq!(pool.prev_post(20), Some(10));
+        assert_eq!(pool.next_post(20), None);
+
+        // not in the pool
+        assert_eq!(pool.prev_post(40), None);
+        assert_eq!(pool.next_post(40), None);
+    }
+
+    #[test]
+    fn pool_search_parameters() {
+        let search = PoolSearch::new()
+            .creator_name("foo bar")
+            .id(vec![1, 2])
+            .category(PoolCategory::Series)
+            .order(PoolSearchOrder::PostCount);
+
+        assert_eq!(
+            serde_urlencoded::to_string(&search).unwrap(),
+            "search%5Bid%5D=1%2C2&search%5Bcreator_name%5D=foo+bar\
+             &search%5Bcategory%5D=series&search%5Border%5D=post_count"
+        );
+        assert_eq!(serde_urlencoded::to_string(PoolSearch::new()).unwrap(), "");
+    }
+
     #[tokio::test]
     async fn pool_search() {
         let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
@@ -371,4 +718,268 @@ mod tests {
 
         assert_eq!(pools, expected);
     }
+
+    #[tokio::test]
+    async fn pool_search_special_characters() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock(
+            "GET",
+            "/pools.json?page=1&search%5Bname_matches%5D=rock_%26_roll%2B%231",
+        )
+        .with_body("[]")
+        .create();
+
+        assert_eq!(
+            client
+                .pool_search(PoolSearch::new().name_matches("rock_&_roll+#1"))
+                .collect::<Vec<_>>()
+                .await,
+            vec![]
+        );
+    }
+
+    #[tokio::test]
+    async fn pool_stream_is_send() {
+        // the stream borrows the client, which has to outlive the spawned task
+        let client: &'static Client = Box::leak(Box::new(
+            Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap(),
+        ));
+
+        let _m = mock("GET", "/pools.json?page=1&search%5Bname_matches%5D=foo")
+            .with_body(include_str!("mocked/pool_search-foo.json"))
+            .create();
+
+        let mut pools = client.pool_search(PoolSearch::new().name_matches("foo"));
+        let first = tokio::spawn(async move { pools.next().await });
+
+        assert!(matches!(first.await.unwrap(), Some(Ok(_))));
+    }
+
+    #[tokio::test]
+    async fn pool_search_from_page_per_page() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let expected: Vec<Rs621Result<Pool>> = serde_json::from_str::<PoolSearchApiResponse>(
+            include_str!("mocked/pool_search-foo.json"),
+        )
+        .unwrap()
+        .into_iter()
+        .map(Ok)
+        .collect();
+
+        let last_id = match expected.last() {
+            Some(Ok(pool)) => pool.id,
+            _ => unreachable!(),
+        };
+
+        let _m = [
+            mock(
+                "GET",
+                "/pools.json?page=b99999&search%5Bname_matches%5D=foo&limit=100",
+            )
+            .with_body(include_str!("mocked/pool_search-foo.json"))
+            .create(),
+            // the next page starts before the last pool received
+            mock(
+                "GET",
+                &format!(
+                    "/pools.json?page=b{}&search%5Bname_matches%5D=foo&limit=100",
+                    last_id
+                )[..],
+            )
+            .with_body("[]")
+            .create(),
+        ];
+
+        let pools: Vec<Rs621Result<Pool>> = client
+            .pool_search_from_page(
+                PoolSearch::new().name_matches("foo").per_page(100),
+                SearchPage::BeforePost(99999),
+            )
+            .collect()
+            .await;
+
+        assert_eq!(pools, expected);
+    }
+
+    #[tokio::test]
+    async fn search_grouped_by_pool() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        // #8595 and #535 are in pool 1 (in reverse order), #535 is in pool 2 too, #2105 isn't in
+        // any pool and #1470 is in a pool that doesn't exist anymore
+        let mut posts: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/id_8595_535_2105_1470.json")).unwrap();
+        posts["posts"][0]["pools"] = serde_json::json!([1]);
+        posts["posts"][1]["pools"] = serde_json::json!([1, 2]);
+        posts["posts"][2]["pools"] = serde_json::json!([]);
+        posts["posts"][3]["pools"] = serde_json::json!([3]);
+
+        let mut pool: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/pool_search-foo.json")).unwrap();
+        let mut pool_1 = pool[0].take();
+        pool_1["id"] = 1.into();
+        pool_1["post_ids"] = serde_json::json!([535, 8595]);
+        let mut pool_2 = pool_1.clone();
+        pool_2["id"] = 2.into();
+        pool_2["post_ids"] = serde_json::json!([535]);
+
+        let _m = [
+            mock("GET", "/posts.json?limit=320&page=1&tags=comic")
+                .with_body(posts.to_string())
+                .create(),
+            mock(
+                "GET",
+                "/pools.json?page=1&search%5Bid%5D=1%2C2%2C3&limit=100",
+            )
+            .with_body(serde_json::json!([pool_2, pool_1]).to_string())
+            .create(),
+            mock(
+                "GET",
+                "/pools.json?page=2&search%5Bid%5D=1%2C2%2C3&limit=100",
+            )
+            .with_body("[]")
+            .create(),
+        ];
+
+        let post =
+            |i: usize| -> Post { serde_json::from_value(posts["posts"][i].clone()).unwrap() };
+        let pool = |p: &serde_json::Value| -> Pool { serde_json::from_value(p.clone()).unwrap() };
+
+        assert_eq!(
+            client.search_grouped_by_pool("comic", 4).await,
+            Ok((
+                vec![
+                    (pool(&pool_1), vec![post(1), post(0)]),
+                    (pool(&pool_2), vec![post(1)]),
+                ],
+                vec![post(2), post(3)],
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn pools_containing() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let mut post: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/id_8595.json")).unwrap();
+        post["post"]["pools"] = serde_json::json!([1, 2]);
+
+        let mut pool: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/pool_search-foo.json")).unwrap();
+        let mut pool_1 = pool[0].take();
+        pool_1["id"] = 1.into();
+        let mut pool_2 = pool_1.clone();
+        pool_2["id"] = 2.into();
+
+        let _m = [
+            mock("GET", "/posts/8595.json")
+                .with_body(post.to_string())
+                .create(),
+            mock("GET", "/pools.json?page=1&search%5Bid%5D=1%2C2&limit=100")
+                .with_body(serde_json::json!([pool_2, pool_1]).to_string())
+                .create(),
+        ];
+
+        let pool = |p: &serde_json::Value| -> Pool { serde_json::from_value(p.clone()).unwrap() };
+
+        assert_eq!(
+            client.pools_containing(8595).collect::<Vec<_>>().await,
+            vec![Ok(pool(&pool_2)), Ok(pool(&pool_1))]
+        );
+    }
+
+    #[tokio::test]
+    async fn pools_containing_no_pool() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let mut post: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/id_8595.json")).unwrap();
+        post["post"]["pools"] = serde_json::json!([]);
+
+        let _m = mock("GET", "/posts/8595.json")
+            .with_body(post.to_string())
+            .create();
+
+        assert_eq!(
+            client.pools_containing(8595).collect::<Vec<_>>().await,
+            vec![]
+        );
+    }
+
+    #[tokio::test]
+    async fn pool_posts() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        // #99 was deleted after the pool was fetched
+        let mut pool: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/pool_search-foo.json")).unwrap();
+        let mut pool = pool[0].take();
+        pool["id"] = 1.into();
+        pool["post_ids"] = serde_json::json!([535, 99, 8595, 1470, 2105]);
+
+        let _m = [
+            mock("GET", "/pools/1.json")
+                .with_body(pool.to_string())
+                .create(),
+            mock("GET", "/posts.json?tags=id%3A535,99,8595,1470,2105")
+                .with_body(include_str!("mocked/id_8595_535_2105_1470.json"))
+                .create(),
+        ];
+
+        let ids = |posts: Vec<Rs621Result<Post>>| -> Vec<Rs621Result<u64>> {
+            posts.into_iter().map(|p| p.map(|p| p.id)).collect()
+        };
+
+        assert_eq!(
+            ids(client.pool_posts(1, PoolPostsMode::Lenient).collect().await),
+            vec![Ok(535), Ok(8595), Ok(1470), Ok(2105)]
+        );
+
+        assert_eq!(
+            ids(client.pool_posts(1, PoolPostsMode::Strict).collect().await),
+            vec![
+                Ok(535),
+                Err(Error::MissingPoolPost {
+                    pool_id: 1,
+                    post_id: 99
+                }),
+                Ok(8595),
+                Ok(1470),
+                Ok(2105),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn pool_post_search() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = [
+            mock(
+                "GET",
+                "/posts.json?limit=320&page=1&tags=pool%3A1%20rating%3As",
+            )
+            .with_body(include_str!("mocked/id_8595_535_2105_1470.json"))
+            .create(),
+            mock(
+                "GET",
+                "/posts.json?limit=320&page=b535&tags=pool%3A1%20rating%3As",
+            )
+            .with_body(r#"{"posts":[]}"#)
+            .create(),
+        ];
+
+        let ids: Vec<u64> = client
+            .pool_post_search(1, &["rating:s"])
+            .map_ok(|post| post.id)
+            .try_collect()
+            .await
+            .unwrap();
+
+        // search order, not pool order
+        assert_eq!(ids, vec![8595, 2105, 1470, 535]);
+    }
 }
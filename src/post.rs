@@ -2,7 +2,7 @@ use crate::error::Error;
 
 use {
     super::{
-        client::{Client, QueryFuture},
+        client::{dedup_posts, query_param, Client, ETagged, Interleave, QueryFuture},
         error::Result as Rs621Result,
     },
     chrono::{offset::Utc, DateTime},
@@ -16,13 +16,66 @@ use {
         de::{self, Error as _, Visitor},
         Deserialize, Deserializer, Serialize,
     },
-    std::{borrow::Borrow, pin::Pin},
+    std::{
+        borrow::Borrow,
+        collections::{HashMap, HashSet, VecDeque},
+        ops::{Bound, RangeBounds},
+        pin::Pin,
+    },
+    url::Url,
 };
 
 /// Chunk size used for iterators performing requests
 const ITER_CHUNK_SIZE: u64 = 320;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+/// Highest page number the server serves, deeper results have to be fetched relative to a post.
+const PAGE_LIMIT: u64 = 750;
+
+/// Maximum number of parents followed by [`Client::root_post`].
+const ROOT_POST_MAX_DEPTH: usize = 100;
+
+/// Maximum number of requests made by [`Client::random_posts`].
+const RANDOM_POSTS_MAX_ATTEMPTS: usize = 10;
+
+/// Domains of the art-hosting sites recognized by [`Post::artist_sources`]: galleries where artists
+/// post their own works, and their image servers. Subdomains match too.
+const ART_SITES: &[&str] = &[
+    "artstation.com",
+    "bsky.app",
+    "deviantart.com",
+    "furaffinity.net",
+    "furrynetwork.com",
+    "inkbunny.net",
+    "itaku.ee",
+    "newgrounds.com",
+    "patreon.com",
+    "pixiv.net",
+    "sofurry.com",
+    "subscribestar.adult",
+    "tumblr.com",
+    "twimg.com",
+    "twitter.com",
+    "weasyl.com",
+    "x.com",
+];
+
+/// Artist tags that don't name an artist, left out of [`Embed::title`].
+const NON_ARTIST_TAGS: &[&str] = &[
+    "anonymous_artist",
+    "avoid_posting",
+    "conditional_dnp",
+    "epilepsy_warning",
+    "sound_warning",
+    "unknown_artist",
+];
+
+/// Maximum length of [`Embed::description_snippet`], in characters.
+const EMBED_SNIPPET_LENGTH: usize = 200;
+
+/// Maximum number of tags the server accepts in a single search query (for regular accounts).
+pub const QUERY_TAG_LIMIT: usize = 40;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum PostFileExtension {
     #[serde(rename = "jpg")]
     Jpeg,
@@ -36,7 +89,21 @@ pub enum PostFileExtension {
     WebM,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+impl PostFileExtension {
+    /// Returns the extension as used in file names, without the leading dot.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PostFileExtension::Jpeg => "jpg",
+            PostFileExtension::Png => "png",
+            PostFileExtension::Gif => "gif",
+            PostFileExtension::Swf => "swf",
+            PostFileExtension::WebM => "webm",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct PostFile {
     pub width: u64,
     pub height: u64,
@@ -46,21 +113,44 @@ pub struct PostFile {
     pub url: Option<String>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct PostPreview {
     pub width: u64,
     pub height: u64,
     pub url: Option<String>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct PostSample {
+    /// Whether the post has a sample distinct from the file. If not, the sample is the file.
+    #[serde(default, deserialize_with = "nullable_bool_from_json")]
+    pub has: bool,
     pub width: u64,
     pub height: u64,
     pub url: Option<String>,
+    /// Other versions of the file, by name (e.g. `480p` for videos).
+    #[serde(default)]
+    pub alternates: HashMap<String, PostAlternate>,
+}
+
+/// Alternate version of a post's file, e.g. a lower resolution encoding of a video.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct PostAlternate {
+    /// The kind of the alternate, e.g. `video`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub width: u64,
+    pub height: u64,
+    /// The URLs of the alternate in each of its formats, `None` for the ones that aren't
+    /// available.
+    pub urls: Vec<Option<String>>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct PostScore {
     pub up: i64,
     pub down: i64,
@@ -89,6 +179,7 @@ impl From<VoteDir> for i8 {
 }
 
 #[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[non_exhaustive]
 pub struct VoteScore {
     pub up: i64,
     pub down: i64,
@@ -120,7 +211,8 @@ impl VoteScore {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, Clone)]
+#[non_exhaustive]
 pub struct PostTags {
     pub general: Vec<String>,
     pub species: Vec<String>,
@@ -131,7 +223,23 @@ pub struct PostTags {
     pub meta: Vec<String>,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+impl PostTags {
+    /// Returns an iterator over the tags of every category.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.general
+            .iter()
+            .chain(&self.species)
+            .chain(&self.character)
+            .chain(&self.artist)
+            .chain(&self.invalid)
+            .chain(&self.lore)
+            .chain(&self.meta)
+            .map(String::as_str)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, Clone)]
+#[non_exhaustive]
 pub struct PostFlags {
     #[serde(deserialize_with = "nullable_bool_from_json")]
     pub pending: bool,
@@ -147,7 +255,7 @@ pub struct PostFlags {
     pub deleted: bool,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, Clone, Copy)]
 pub enum PostRating {
     #[serde(rename = "s")]
     Safe,
@@ -157,7 +265,8 @@ pub enum PostRating {
     Explicit,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, Clone)]
+#[non_exhaustive]
 pub struct PostRelationships {
     pub parent_id: Option<u64>,
     pub has_children: bool,
@@ -166,16 +275,20 @@ pub struct PostRelationships {
 }
 
 /// Structure representing a post.
-#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, Clone)]
+#[non_exhaustive]
 pub struct Post {
     pub id: u64,
+    #[serde(deserialize_with = "crate::datetime::deserialize")]
     pub created_at: DateTime<Utc>,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_option")]
     pub updated_at: Option<DateTime<Utc>>,
     pub file: PostFile,
     pub preview: PostPreview,
     pub sample: Option<PostSample>,
     pub score: PostScore,
     pub tags: PostTags,
+    /// Tags locked by an admin, as a flat list. See [`Post::locked_tags_by_category`].
     pub locked_tags: Vec<String>,
     pub change_seq: u64,
     pub flags: PostFlags,
@@ -191,6 +304,387 @@ pub struct Post {
     pub is_favorited: bool,
 }
 
+#[cfg(any(test, feature = "test-util"))]
+impl Post {
+    /// Returns a blank post with the given ID, to build posts in tests now that they can't be
+    /// built with a struct expression. Fields are public, so set the ones that matter:
+    ///
+    /// ```
+    /// # use rs621::post::{Post, PostRating};
+    /// let mut post = Post::new(8595);
+    /// post.rating = PostRating::Explicit;
+    /// post.tags.general.push("fluffy".into());
+    /// ```
+    ///
+    /// Only available with the `test-util` feature.
+    pub fn new(id: u64) -> Self {
+        Post {
+            id,
+            created_at: DateTime::<Utc>::default(),
+            updated_at: None,
+            file: PostFile {
+                width: 0,
+                height: 0,
+                ext: PostFileExtension::Png,
+                size: 0,
+                md5: String::new(),
+                url: None,
+            },
+            preview: PostPreview {
+                width: 0,
+                height: 0,
+                url: None,
+            },
+            sample: None,
+            score: PostScore {
+                up: 0,
+                down: 0,
+                total: 0,
+            },
+            tags: PostTags {
+                general: Vec::new(),
+                species: Vec::new(),
+                character: Vec::new(),
+                artist: Vec::new(),
+                invalid: Vec::new(),
+                lore: Vec::new(),
+                meta: Vec::new(),
+            },
+            locked_tags: Vec::new(),
+            change_seq: 0,
+            flags: PostFlags {
+                pending: false,
+                flagged: false,
+                note_locked: false,
+                status_locked: false,
+                rating_locked: false,
+                deleted: false,
+            },
+            rating: PostRating::Safe,
+            fav_count: 0,
+            sources: Vec::new(),
+            pools: Vec::new(),
+            relationships: PostRelationships {
+                parent_id: None,
+                has_children: false,
+                has_active_children: false,
+                children: Vec::new(),
+            },
+            approver_id: None,
+            uploader_id: 0,
+            description: String::new(),
+            comment_count: 0,
+            is_favorited: false,
+        }
+    }
+}
+
+impl Post {
+    /// Checks invariants of the post that the server is expected to uphold. See
+    /// [`ClientBuilder::validate_responses`](crate::client::ClientBuilder::validate_responses).
+    fn check_invariants(&self) -> Rs621Result<()> {
+        let reason = if self.score.total != self.score.up + self.score.down {
+            "score total isn't the sum of up and down votes"
+        } else if !self.flags.deleted && self.file.md5.is_empty() {
+            "file has no MD5 hash"
+        } else if self.file.url.is_some() && self.preview.url.is_none() {
+            "file has an URL but its preview doesn't"
+        } else {
+            return Ok(());
+        };
+
+        Err(Error::Invalid {
+            post_id: self.id,
+            reason: String::from(reason),
+        })
+    }
+
+    /// Returns the fields of the post needed to show it in a grid.
+    pub fn thumbnail(&self) -> PostThumbnail {
+        PostThumbnail {
+            id: self.id,
+            preview_url: self.preview.url.clone(),
+            rating: self.rating,
+            score_total: self.score.total,
+        }
+    }
+
+    /// Returns the locked tags sorted by category, by looking them up in [`Post::tags`]. Locked
+    /// tags that the post doesn't have (e.g. `-tag`, locking a tag out) aren't included.
+    pub fn locked_tags_by_category(&self) -> PostTags {
+        let locked = |tags: &[String]| {
+            tags.iter()
+                .filter(|t| self.locked_tags.contains(t))
+                .cloned()
+                .collect()
+        };
+
+        PostTags {
+            general: locked(&self.tags.general),
+            species: locked(&self.tags.species),
+            character: locked(&self.tags.character),
+            artist: locked(&self.tags.artist),
+            invalid: locked(&self.tags.invalid),
+            lore: locked(&self.tags.lore),
+            meta: locked(&self.tags.meta),
+        }
+    }
+
+    /// Returns the name the file has on the server, `{md5}.{ext}`, or `None` if the file can't be
+    /// downloaded (e.g. because the post was deleted).
+    pub fn file_name(&self) -> Option<String> {
+        match self.file.url {
+            Some(_) if !self.file.md5.is_empty() => {
+                Some(format!("{}.{}", self.file.md5, self.file.ext.as_str()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the description as plain text, without its DText markup. See
+    /// [`strip_dtext`](crate::dtext::strip_dtext).
+    pub fn plain_description(&self) -> String {
+        crate::dtext::strip_dtext(&self.description)
+    }
+
+    /// Returns a summary of the post to share it, e.g. in a chat message. The client is only used
+    /// for the URL of the post's page.
+    ///
+    /// The title is made of the first character tag and the artist tags (e.g. `"krystal by foo,
+    /// bar"`), falling back to the ID of the post (`"Post #8595 by foo"`, `"Post #8595"`).
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// let post = client.get_post(8595).await?;
+    ///
+    /// let embed = post.embed(&client);
+    /// println!("[{}]({})", embed.title, embed.page_url);
+    /// # Ok(()) }
+    /// ```
+    pub fn embed(&self, client: &Client) -> Embed {
+        let humanize = |tag: &String| tag.replace('_', " ");
+
+        let artists = self
+            .tags
+            .artist
+            .iter()
+            .filter(|tag| !NON_ARTIST_TAGS.contains(&tag.as_str()))
+            .map(humanize)
+            .join(", ");
+
+        let subject = match self.tags.character.first() {
+            Some(character) => humanize(character),
+            None => format!("Post #{}", self.id),
+        };
+
+        let title = if artists.is_empty() {
+            subject
+        } else {
+            format!("{} by {}", subject, artists)
+        };
+
+        let description = self.plain_description().split_whitespace().join(" ");
+        let description_snippet = match description.char_indices().nth(EMBED_SNIPPET_LENGTH) {
+            Some((end, _)) => format!("{}…", description[..end].trim_end()),
+            None => description,
+        };
+
+        Embed {
+            title,
+            description_snippet,
+            thumbnail_url: self.preview.url.clone(),
+            // the base URL was already parsed, and joining it with a path can't fail
+            page_url: client
+                .public_url(&format!("/posts/{}", self.id))
+                .expect("invalid post URL"),
+            width: self.file.width,
+            height: self.file.height,
+            rating: self.rating,
+        }
+    }
+
+    /// Returns the URL of the image that best fits a `target_width` pixels wide display, among
+    /// the preview, the sample and the file: the narrowest one at least that wide, or the widest
+    /// one if none is. Returns `None` if none of them can be downloaded.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// let post = client.get_post(8595).await?;
+    ///
+    /// if let Some(url) = post.url_for_width(600) {
+    ///     println!("<img src=\"{}\">", url);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn url_for_width(&self, target_width: u64) -> Option<&str> {
+        let sample = self
+            .sample
+            .as_ref()
+            .filter(|sample| sample.has)
+            .map(|sample| (sample.width, &sample.url));
+
+        let candidates = std::iter::once((self.preview.width, &self.preview.url))
+            .chain(sample)
+            .chain(std::iter::once((self.file.width, &self.file.url)))
+            .filter_map(|(width, url)| url.as_deref().map(|url| (width, url)));
+
+        let mut best: Option<(u64, &str)> = None;
+        for (width, url) in candidates {
+            let better = match best {
+                // the narrowest image that is wide enough
+                Some((best_width, _)) if best_width >= target_width => {
+                    width >= target_width && width < best_width
+                }
+                // otherwise, anything wider is better
+                Some((best_width, _)) => width > best_width,
+                None => true,
+            };
+
+            if better {
+                best = Some((width, url));
+            }
+        }
+
+        best.map(|(_, url)| url)
+    }
+
+    /// Returns the sources that are web URLs, parsed. Free text sources (e.g. the artist's name)
+    /// are skipped.
+    pub fn source_urls(&self) -> Vec<Url> {
+        self.sources
+            .iter()
+            .filter_map(|source| Url::parse(source.trim()).ok())
+            .filter(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some())
+            .collect()
+    }
+
+    /// Returns `true` if one of the sources is an URL from `domain` or one of its subdomains
+    /// (e.g. `furaffinity.net` matches `www.furaffinity.net`).
+    pub fn has_source_from(&self, domain: &str) -> bool {
+        let domain = domain.trim_matches('.').to_ascii_lowercase();
+
+        self.source_urls()
+            .iter()
+            .any(|url| matches!(url.host_str(), Some(host) if is_host_in(host, &domain)))
+    }
+
+    /// Returns the first source, which is usually where the artist posted the work.
+    pub fn primary_source(&self) -> Option<&str> {
+        self.sources
+            .iter()
+            .map(|source| source.trim())
+            .find(|source| !source.is_empty())
+    }
+
+    /// Returns the sources that are URLs from well-known art-hosting sites (FurAffinity,
+    /// DeviantArt, Twitter, Inkbunny, etc.), in their original order. Useful to credit the artist
+    /// with a link to their gallery rather than to a repost.
+    pub fn artist_sources(&self) -> Vec<&str> {
+        self.sources
+            .iter()
+            .map(|source| source.trim())
+            .filter(|source| match Url::parse(source) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => url
+                    .host_str()
+                    .into_iter()
+                    .any(|host| ART_SITES.iter().any(|site| is_host_in(host, site))),
+                _ => false,
+            })
+            .collect()
+    }
+
+    /// Returns `true` if the post is tagged `conditional_dnp`, meaning the artist only allows some
+    /// of their works to be posted. The tag is looked up in the artist, meta and lore tags (it's
+    /// usually an artist tag).
+    pub fn is_conditional_dnp(&self) -> bool {
+        self.has_dnp_tag("conditional_dnp")
+    }
+
+    /// Returns `true` if the post is tagged `avoid_posting`, meaning the artist is on the Do Not
+    /// Post list. The tag is looked up in the artist, meta and lore tags (it's usually an artist
+    /// tag).
+    pub fn is_avoid_posting(&self) -> bool {
+        self.has_dnp_tag("avoid_posting")
+    }
+
+    fn has_dnp_tag(&self, tag: &str) -> bool {
+        self.tags
+            .artist
+            .iter()
+            .chain(&self.tags.meta)
+            .chain(&self.tags.lore)
+            .any(|t| t == tag)
+    }
+
+    /// Returns `true` if the post is tagged `sound`, meaning it's a video or flash with audio.
+    pub fn has_sound(&self) -> bool {
+        self.tags.meta.iter().any(|t| t == "sound")
+    }
+
+    /// Time elapsed since the post was created.
+    pub fn age(&self) -> chrono::Duration {
+        Utc::now() - self.created_at
+    }
+
+    /// Time elapsed since the post was last updated, or `None` if it never was.
+    pub fn updated_age(&self) -> Option<chrono::Duration> {
+        self.updated_at.map(|updated_at| Utc::now() - updated_at)
+    }
+}
+
+/// The few fields of a [`Post`] needed to show it in a grid. See [`Post::thumbnail`] and
+/// [`Client::post_search_thumbnails`].
+#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[serde(from = "RawPostThumbnail")]
+#[non_exhaustive]
+pub struct PostThumbnail {
+    pub id: u64,
+    pub preview_url: Option<String>,
+    pub rating: PostRating,
+    pub score_total: i64,
+}
+
+/// A summary of a post for link previews, see [`Post::embed`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct Embed {
+    /// The first character and the artists, e.g. `"krystal by some artist"`.
+    pub title: String,
+    /// The beginning of the description as plain text, up to 200 characters.
+    pub description_snippet: String,
+    pub thumbnail_url: Option<String>,
+    pub page_url: Url,
+    pub width: u64,
+    pub height: u64,
+    pub rating: PostRating,
+}
+
+/// The parts of a post's JSON making up a [`PostThumbnail`].
+#[derive(Deserialize)]
+struct RawPostThumbnail {
+    id: u64,
+    preview: PostPreview,
+    rating: PostRating,
+    score: PostScore,
+}
+
+impl From<RawPostThumbnail> for PostThumbnail {
+    fn from(raw: RawPostThumbnail) -> Self {
+        PostThumbnail {
+            id: raw.id,
+            preview_url: raw.preview.url,
+            rating: raw.rating,
+            score_total: raw.score.total,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Deserialize)]
 struct PostListApiResponse {
     pub posts: Vec<Post>,
@@ -226,130 +720,544 @@ where
     de.deserialize_any(NullableBoolVisitor)
 }
 
+/// Sort orders for post searches, used with [`Query::order`] and [`Query::order_asc`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PostOrder {
+    Id,
+    Score,
+    Favorites,
+    CreatedAt,
+    UpdatedAt,
+    Comments,
+    Mpixels,
+    FileSize,
+    TagCount,
+    /// Random order. It has no direction.
+    Random,
+}
+
+impl PostOrder {
+    /// Value of the `order:` meta tag for this order, in the given direction.
+    fn meta_tag_value(self, ascending: bool) -> &'static str {
+        match (self, ascending) {
+            (PostOrder::Id, false) => "id_desc",
+            (PostOrder::Id, true) => "id_asc",
+            (PostOrder::Score, false) => "score",
+            (PostOrder::Score, true) => "score_asc",
+            (PostOrder::Favorites, false) => "favcount",
+            (PostOrder::Favorites, true) => "favcount_asc",
+            (PostOrder::CreatedAt, false) => "created_at",
+            (PostOrder::CreatedAt, true) => "created_at_asc",
+            (PostOrder::UpdatedAt, false) => "updated",
+            (PostOrder::UpdatedAt, true) => "updated_asc",
+            (PostOrder::Comments, false) => "comment_count",
+            (PostOrder::Comments, true) => "comment_count_asc",
+            (PostOrder::Mpixels, false) => "mpixels",
+            (PostOrder::Mpixels, true) => "mpixels_asc",
+            (PostOrder::FileSize, false) => "filesize",
+            (PostOrder::FileSize, true) => "filesize_asc",
+            (PostOrder::TagCount, false) => "tagcount",
+            (PostOrder::TagCount, true) => "tagcount_asc",
+            (PostOrder::Random, _) => "random",
+        }
+    }
+}
+
+/// Escapes a value used in a meta tag (e.g. a username), so that it stays a single tag: tags are
+/// separated by whitespace, and usernames use underscores in place of spaces anyway.
+fn escape_meta_value(value: &str) -> String {
+    value.split_whitespace().join("_")
+}
+
+/// Formats a range for a meta tag using the server's syntax (`>=N`, `<=N`, `N..M` or `N`, the
+/// bounds being inclusive), or returns `None` if the range is unbounded.
+fn range_meta_value<R: RangeBounds<u64>>(range: R) -> Option<String> {
+    let min = match range.start_bound() {
+        Bound::Included(&a) => Some(a),
+        Bound::Excluded(&a) => Some(a.saturating_add(1)),
+        Bound::Unbounded => None,
+    };
+
+    let max = match range.end_bound() {
+        Bound::Included(&b) => Some(b),
+        // nothing can be below 0, but there's no inclusive way to say it
+        Bound::Excluded(&0) => return Some(String::from("<0")),
+        Bound::Excluded(&b) => Some(b - 1),
+        Bound::Unbounded => None,
+    };
+
+    match (min, max) {
+        (None, None) => None,
+        (Some(a), None) => Some(format!(">={}", a)),
+        (None, Some(b)) => Some(format!("<={}", b)),
+        (Some(a), Some(b)) if a == b => Some(format!("{}", a)),
+        (Some(a), Some(b)) => Some(format!("{}..{}", a, b)),
+    }
+}
+
+/// Returns `true` if `host` is `domain` or one of its subdomains. `domain` must be lowercase.
+fn is_host_in(host: &str, domain: &str) -> bool {
+    host == domain || matches!(host.strip_suffix(domain), Some(sub) if sub.ends_with('.'))
+}
+
 /// A search query. Contains information about the tags used and an URL encoded version of the tags.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct Query {
+    tags: Vec<String>,
     url_encoded_tags: String,
     ordered: bool,
 }
 
-impl<T> From<&[T]> for Query
-where
-    T: AsRef<str>,
-{
-    fn from(q: &[T]) -> Self {
-        let tags: Vec<&str> = q.iter().map(|t| t.as_ref()).collect();
-        let query_str = tags.join(" ");
-        let url_encoded_tags = urlencoding::encode(&query_str);
+impl Query {
+    fn from_tags(tags: Vec<String>) -> Self {
+        // characters like `+`, `&` and `#` are escaped, so they can't be taken for separators
+        let url_encoded_tags = urlencoding::encode(&tags.join(" "));
         let ordered = tags.iter().any(|t| t.starts_with("order:"));
 
         Query {
+            tags,
             url_encoded_tags,
             ordered,
         }
     }
-}
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub enum SearchPage {
-    Page(u64),
-    BeforePost(u64),
-    AfterPost(u64),
-}
+    /// Creates an empty query, matching every post.
+    pub fn new() -> Self {
+        Query::default()
+    }
 
-/// Iterator returning posts from a search query.
-#[derive(Derivative)]
-#[derivative(Debug)]
-pub struct PostSearchStream<'a> {
-    client: &'a Client,
-    query: Query,
+    /// Sorts the results in descending order (highest score, most recent, etc. first). Replaces
+    /// any `order:` tag already present. Ordered queries are fetched page by page.
+    ///
+    /// ```
+    /// # use rs621::post::{PostOrder, Query};
+    /// let query = Query::from(&["fluffy"][..]).order(PostOrder::Score);
+    /// assert_eq!(query.tags(), vec!["fluffy", "order:score"]);
+    /// ```
+    pub fn order(self, order: PostOrder) -> Self {
+        self.with_order(order, false)
+    }
 
-    query_url: Option<String>,
+    /// Sorts the results in ascending order. Replaces any `order:` tag already present.
+    pub fn order_asc(self, order: PostOrder) -> Self {
+        self.with_order(order, true)
+    }
 
-    #[derivative(Debug = "ignore")]
-    query_future: Option<Pin<QueryFuture>>,
+    fn with_order(self, order: PostOrder, ascending: bool) -> Self {
+        let mut tags = self.tags;
+        tags.retain(|t| !t.starts_with("order:"));
+        tags.push(format!("order:{}", order.meta_tag_value(ascending)));
 
-    next_page: SearchPage,
-    chunk: Vec<Rs621Result<Post>>,
-    ended: bool,
-}
+        Query::from_tags(tags)
+    }
 
-impl<'a> PostSearchStream<'a> {
-    fn new<T: Into<Query>>(client: &'a Client, query: T, page: SearchPage) -> Self {
-        PostSearchStream {
-            client: client,
-            query: query.into(),
+    /// Only matches posts upvoted by `user`, or by the logged in user if `None` (`votedup:me`,
+    /// which requires the client to be logged in).
+    ///
+    /// ```
+    /// # use rs621::post::Query;
+    /// let query = Query::from(&["fluffy"][..]).voted_up_by(None);
+    /// assert_eq!(query.tags(), vec!["fluffy", "votedup:me"]);
+    /// ```
+    pub fn voted_up_by(self, user: Option<&str>) -> Self {
+        self.with_user_meta_tag("votedup", user)
+    }
 
-            query_url: None,
-            query_future: None,
+    /// Only matches posts downvoted by `user`, or by the logged in user if `None`
+    /// (`voteddown:me`, which requires the client to be logged in).
+    pub fn voted_down_by(self, user: Option<&str>) -> Self {
+        self.with_user_meta_tag("voteddown", user)
+    }
 
-            next_page: page,
-            chunk: Vec::new(),
-            ended: false,
-        }
+    /// Only matches posts voted on (either way) by `user`, or by the logged in user if `None`
+    /// (`voted:me`, which requires the client to be logged in).
+    pub fn voted_by(self, user: Option<&str>) -> Self {
+        self.with_user_meta_tag("voted", user)
     }
-}
 
-impl<'a> Stream for PostSearchStream<'a> {
-    type Item = Rs621Result<Post>;
+    /// Only matches posts whose favorite count is in `range` (`favcount:`).
+    ///
+    /// ```
+    /// # use rs621::post::Query;
+    /// let query = Query::from("fluffy").fav_count_range(100..).comment_count_range(..=10);
+    /// assert_eq!(query.tags(), vec!["fluffy", "favcount:>=100", "comment_count:<=10"]);
+    /// ```
+    pub fn fav_count_range<R: RangeBounds<u64>>(self, range: R) -> Self {
+        self.with_range_meta_tag("favcount", range)
+    }
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Rs621Result<Post>>> {
-        enum QueryPollRes {
-            Pending,
-            Err(crate::error::Error),
-            NotFetching,
-        }
+    /// Only matches posts whose comment count is in `range` (`comment_count:`).
+    pub fn comment_count_range<R: RangeBounds<u64>>(self, range: R) -> Self {
+        self.with_range_meta_tag("comment_count", range)
+    }
 
-        let this = self.get_mut();
+    /// Only matches posts approved by `user` (`approver:`). Note that the server interprets
+    /// `none` and `any` specially: use [`Query::unapproved`] rather than `approved_by("none")`.
+    ///
+    /// ```
+    /// # use rs621::post::Query;
+    /// let query = Query::from("fluffy").approved_by("some janitor");
+    /// assert_eq!(query.tags(), vec!["fluffy", "approver:some_janitor"]);
+    /// ```
+    pub fn approved_by(self, user: &str) -> Self {
+        self.with_tag(format!("approver:{}", escape_meta_value(user)))
+    }
 
-        loop {
-            // poll the pending query future if there's any
-            let query_status = if let Some(ref mut fut) = this.query_future {
-                match fut.as_mut().poll(cx) {
-                    Poll::Ready(res) => {
-                        // the future is finished, drop it
-                        this.query_future = None;
+    /// Only matches posts that weren't approved by anyone (`approver:none`).
+    pub fn unapproved(self) -> Self {
+        self.with_tag(String::from("approver:none"))
+    }
 
-                        match res {
-                            Ok(body) => {
-                                // put everything in the chunk
-                                this.chunk =
-                                    match serde_json::from_value::<PostListApiResponse>(body) {
-                                        Ok(res) => res
-                                            .posts
-                                            .into_iter()
-                                            .rev()
-                                            .map(|post| Ok(post))
-                                            .collect(),
-                                        Err(e) => vec![Err(Error::Serial(format!("{}", e)))],
-                                    };
+    /// Only matches posts uploaded by `user` (`user:`). Use [`Query::uploaded_by_id`] if you
+    /// only have the ID of the uploader, e.g. from [`Post::uploader_id`].
+    ///
+    /// ```
+    /// # use rs621::post::Query;
+    /// let query = Query::from("fluffy").uploaded_by("some artist");
+    /// assert_eq!(query.tags(), vec!["fluffy", "user:some_artist"]);
+    /// ```
+    pub fn uploaded_by(self, user: &str) -> Self {
+        self.with_tag(format!("user:{}", escape_meta_value(user)))
+    }
 
-                                let last_id = match this.chunk.first() {
-                                    Some(Ok(post)) => post.id,
-                                    _ => 0,
-                                };
+    /// Only matches posts uploaded by the user with the given ID (`user_id:`).
+    ///
+    /// ```
+    /// # use rs621::post::Query;
+    /// let query = Query::from("fluffy").uploaded_by_id(1234);
+    /// assert_eq!(query.tags(), vec!["fluffy", "user_id:1234"]);
+    /// ```
+    pub fn uploaded_by_id(self, user_id: u64) -> Self {
+        self.with_tag(format!("user_id:{}", user_id))
+    }
 
-                                // we now know what will be the next page
-                                this.next_page = if this.query.ordered {
-                                    match this.next_page {
-                                        SearchPage::Page(i) => SearchPage::Page(i + 1),
-                                        _ => SearchPage::Page(1),
-                                    }
-                                } else {
-                                    match this.next_page {
-                                        SearchPage::Page(_) => SearchPage::BeforePost(last_id),
-                                        SearchPage::BeforePost(_) => {
-                                            SearchPage::BeforePost(last_id)
-                                        }
-                                        SearchPage::AfterPost(_) => SearchPage::AfterPost(last_id),
-                                    }
-                                };
+    /// Only matches the children of the post with the given ID (`parent:`).
+    ///
+    /// ```
+    /// # use rs621::post::Query;
+    /// let query = Query::new().parent(8595);
+    /// assert_eq!(query.tags(), vec!["parent:8595"]);
+    /// ```
+    pub fn parent(self, id: u64) -> Self {
+        self.with_tag(format!("parent:{}", id))
+    }
 
-                                // mark the stream as ended if there was no posts
-                                this.ended = this.chunk.is_empty();
-                                QueryPollRes::NotFetching
+    /// Only matches posts that don't have a parent (`parent:none`).
+    pub fn parent_none(self) -> Self {
+        self.with_tag(String::from("parent:none"))
+    }
+
+    /// Only matches posts that have children (`isparent:true`), or that don't if `has_children`
+    /// is `false` (`isparent:false`).
+    ///
+    /// ```
+    /// # use rs621::post::Query;
+    /// let query = Query::from("comic").has_children(true).parent_none();
+    /// assert_eq!(query.tags(), vec!["comic", "isparent:true", "parent:none"]);
+    /// ```
+    pub fn has_children(self, has_children: bool) -> Self {
+        self.with_tag(format!("isparent:{}", has_children))
+    }
+
+    /// Only matches posts with audio (`sound`), or without if `has_sound` is `false` (`-sound`).
+    ///
+    /// ```
+    /// # use rs621::post::Query;
+    /// let query = Query::from("animated").has_sound(true);
+    /// assert_eq!(query.tags(), vec!["animated", "sound"]);
+    /// ```
+    pub fn has_sound(self, has_sound: bool) -> Self {
+        self.with_tag(String::from(if has_sound { "sound" } else { "-sound" }))
+    }
+
+    /// Only matches posts that are in at least one pool (`inpool:true`), or in none if
+    /// `in_pool` is `false` (`inpool:false`).
+    ///
+    /// ```
+    /// # use rs621::post::Query;
+    /// let query = Query::from("comic").in_pool(false);
+    /// assert_eq!(query.tags(), vec!["comic", "inpool:false"]);
+    /// ```
+    pub fn in_pool(self, in_pool: bool) -> Self {
+        self.with_tag(format!("inpool:{}", in_pool))
+    }
+
+    /// Only matches posts that aren't in any pool (`pool:none`).
+    pub fn no_pool(self) -> Self {
+        self.with_tag(String::from("pool:none"))
+    }
+
+    fn with_range_meta_tag<R: RangeBounds<u64>>(self, meta: &str, range: R) -> Self {
+        match range_meta_value(range) {
+            Some(value) => self.with_tag(format!("{}:{}", meta, value)),
+            None => self,
+        }
+    }
+
+    fn with_user_meta_tag(self, meta: &str, user: Option<&str>) -> Self {
+        self.with_tag(format!(
+            "{}:{}",
+            meta,
+            escape_meta_value(user.unwrap_or("me"))
+        ))
+    }
+
+    fn with_tag(self, tag: String) -> Self {
+        let mut tags = self.tags;
+        tags.push(tag);
+
+        Query::from_tags(tags)
+    }
+
+    /// Returns the tags of the query, as they're sent to the server.
+    pub fn tags(&self) -> Vec<String> {
+        self.tags.clone()
+    }
+
+    /// Returns `true` if the query contains an `order:` tag, in which case results are fetched
+    /// page by page rather than relative to the last post received.
+    pub fn is_ordered(&self) -> bool {
+        self.ordered
+    }
+
+    /// Returns `true` if the results are sorted newest first explicitly (`order:id_desc`).
+    fn is_by_id_desc(&self) -> bool {
+        self.tags.iter().any(|t| t == "order:id_desc")
+    }
+
+    /// Checks that the query can be sent to the server, failing with [`Error::TooManyTags`] if it
+    /// has more than [`QUERY_TAG_LIMIT`] tags. Searches perform this check before making any
+    /// request.
+    pub fn validate(&self) -> Rs621Result<()> {
+        if self.tags.len() > QUERY_TAG_LIMIT {
+            Err(Error::TooManyTags {
+                count: self.tags.len(),
+                limit: QUERY_TAG_LIMIT,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<T> From<&[T]> for Query
+where
+    T: AsRef<str>,
+{
+    fn from(q: &[T]) -> Self {
+        Query::from_tags(
+            q.iter()
+                .flat_map(|t| t.as_ref().split_ascii_whitespace())
+                .map(String::from)
+                .collect(),
+        )
+    }
+}
+
+impl<T> From<Vec<T>> for Query
+where
+    T: AsRef<str>,
+{
+    fn from(q: Vec<T>) -> Self {
+        Query::from(&q[..])
+    }
+}
+
+/// Splits the tags on whitespace, like the search box of the website does.
+impl From<&str> for Query {
+    fn from(q: &str) -> Self {
+        Query::from(&[q][..])
+    }
+}
+
+/// A client-side blacklist, used to hide posts based on their tags.
+///
+/// Each entry is a tag name, in which `*` matches any sequence of characters. An entry prefixed
+/// with `-` is an exception: posts having a matching tag are never hidden, even if they match
+/// other entries.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Blacklist {
+    hidden: Vec<String>,
+    allowed: Vec<String>,
+}
+
+impl Blacklist {
+    pub fn new<T: AsRef<str>>(entries: &[T]) -> Self {
+        let mut blacklist = Blacklist::default();
+
+        for entry in entries.iter().map(|e| e.as_ref().trim()) {
+            match entry.strip_prefix('-') {
+                Some(allowed) if !allowed.is_empty() => blacklist.allowed.push(allowed.into()),
+                Some(_) => (),
+                None if !entry.is_empty() => blacklist.hidden.push(entry.into()),
+                None => (),
+            }
+        }
+
+        blacklist
+    }
+
+    /// Returns `true` if the post should be hidden.
+    pub fn is_blacklisted(&self, post: &Post) -> bool {
+        let matches = |patterns: &[String]| {
+            post.tags
+                .iter()
+                .any(|tag| patterns.iter().any(|p| wildcard_match(p, tag)))
+        };
+
+        matches(&self.hidden) && !matches(&self.allowed)
+    }
+}
+
+/// Matches `text` against `pattern`, where `*` matches any (possibly empty) sequence of characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+
+    // the first part has to match the start, unless the pattern starts with `*`
+    let mut rest = match text.strip_prefix(parts.next().unwrap_or("")) {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    let mut parts = parts.peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            // the last part has to match the end
+            return rest.ends_with(part);
+        }
+
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+
+    // there was no `*` at all
+    rest.is_empty()
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SearchPage {
+    Page(u64),
+    BeforePost(u64),
+    AfterPost(u64),
+}
+
+/// Iterator returning posts from a search query, as raw JSON values. See
+/// [`Client::post_search_raw`].
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct PostSearchRawStream<'a> {
+    client: &'a Client,
+    query: Query,
+    /// Additional query parameters, already encoded, each starting with `&`.
+    extra_params: String,
+
+    query_url: Option<String>,
+
+    #[derivative(Debug = "ignore")]
+    query_future: Option<Pin<QueryFuture>>,
+
+    next_page: SearchPage,
+    /// Posts of the current page.
+    #[derivative(Debug = "ignore")]
+    chunk: VecDeque<serde_json::Value>,
+    ended: bool,
+}
+
+impl<'a> PostSearchRawStream<'a> {
+    fn new<T: Into<Query>>(client: &'a Client, query: T, page: SearchPage) -> Self {
+        PostSearchRawStream {
+            client: client,
+            query: query.into(),
+            extra_params: String::new(),
+
+            query_url: None,
+            query_future: None,
+
+            next_page: page,
+            chunk: VecDeque::new(),
+            ended: false,
+        }
+    }
+}
+
+impl<'a> Stream for PostSearchRawStream<'a> {
+    type Item = Rs621Result<serde_json::Value>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Rs621Result<serde_json::Value>>> {
+        enum QueryPollRes {
+            Pending,
+            Err(crate::error::Error),
+            NotFetching,
+        }
+
+        let this = self.get_mut();
+
+        loop {
+            // poll the pending query future if there's any
+            let query_status = if let Some(ref mut fut) = this.query_future {
+                match fut.as_mut().poll(cx) {
+                    Poll::Ready(res) => {
+                        // the future is finished, drop it
+                        this.query_future = None;
+
+                        match res {
+                            Ok(mut body) => {
+                                // put everything in the chunk
+                                match body["posts"].take() {
+                                    serde_json::Value::Array(posts) => this.chunk = posts.into(),
+                                    _ => {
+                                        this.ended = true;
+                                        return Poll::Ready(Some(Err(Error::Serial(
+                                            String::from("missing field `posts`"),
+                                        ))));
+                                    }
+                                }
+
+                                let last_id = this
+                                    .chunk
+                                    .back()
+                                    .and_then(|post| post["id"].as_u64())
+                                    .unwrap_or(0);
+
+                                // we now know what will be the next page
+                                this.next_page = if this.query.ordered {
+                                    let by_id_desc = this.query.is_by_id_desc();
+
+                                    match this.next_page {
+                                        // the server refuses pages past the limit, but results
+                                        // by descending ID can go on from the last post instead
+                                        SearchPage::Page(i) if i >= PAGE_LIMIT && by_id_desc => {
+                                            SearchPage::BeforePost(last_id)
+                                        }
+                                        SearchPage::Page(i) => SearchPage::Page(i + 1),
+                                        SearchPage::BeforePost(_) if by_id_desc => {
+                                            SearchPage::BeforePost(last_id)
+                                        }
+                                        _ => SearchPage::Page(1),
+                                    }
+                                } else {
+                                    match this.next_page {
+                                        SearchPage::Page(_) => SearchPage::BeforePost(last_id),
+                                        SearchPage::BeforePost(_) => {
+                                            SearchPage::BeforePost(last_id)
+                                        }
+                                        SearchPage::AfterPost(_) => SearchPage::AfterPost(last_id),
+                                    }
+                                };
+
+                                // mark the stream as ended if there was no posts
+                                this.ended = this.chunk.is_empty();
+                                QueryPollRes::NotFetching
                             }
 
+                            // a timed out page is fetched again if the stream is polled further
+                            Err(Error::Timeout) => QueryPollRes::Err(Error::Timeout),
+
                             // if there was an error, stream it and mark the stream as ended
                             Err(e) => {
                                 this.ended = true;
@@ -374,37 +1282,112 @@ impl<'a> Stream for PostSearchStream<'a> {
                     return Poll::Ready(None);
                 }
                 QueryPollRes::NotFetching if !this.chunk.is_empty() => {
-                    // get a post
-                    let post = this.chunk.pop().unwrap();
-
-                    // stream the post
-                    return Poll::Ready(Some(post));
+                    // stream a post
+                    return Poll::Ready(this.chunk.pop_front().map(Ok));
                 }
                 QueryPollRes::NotFetching => {
+                    // don't even bother asking the server if it will reject the query
+                    if let Err(e) = this.query.validate() {
+                        this.ended = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+
                     // we need to load a new chunk of posts
                     let url = format!(
-                        "/posts.json?limit={}&page={}&tags={}",
+                        "/posts.json?limit={}&page={}&tags={}{}",
                         ITER_CHUNK_SIZE,
                         match this.next_page {
                             SearchPage::Page(i) => format!("{}", i),
                             SearchPage::BeforePost(i) => format!("b{}", i),
                             SearchPage::AfterPost(i) => format!("a{}", i),
                         },
-                        this.query.url_encoded_tags
+                        this.query.url_encoded_tags,
+                        this.extra_params,
                     );
                     this.query_url = Some(url);
 
                     // get the JSON
-                    this.query_future = Some(Box::pin(
-                        this.client
-                            .get_json_endpoint(this.query_url.as_ref().unwrap()),
-                    ));
+                    this.query_future =
+                        Some(this.client.get_json_page(this.query_url.as_ref().unwrap()));
                 }
             }
         }
     }
 }
 
+impl<'a> PostSearchRawStream<'a> {
+    fn with_params(mut self, extra: &[(&str, &str)]) -> Self {
+        for (key, value) in extra {
+            self.extra_params.push('&');
+            self.extra_params.push_str(&query_param(key, value));
+        }
+
+        self
+    }
+}
+
+/// Iterator returning posts from a search query.
+#[derive(Debug)]
+pub struct PostSearchStream<'a> {
+    raw: PostSearchRawStream<'a>,
+}
+
+impl<'a> PostSearchStream<'a> {
+    fn new<T: Into<Query>>(client: &'a Client, query: T, page: SearchPage) -> Self {
+        PostSearchStream {
+            raw: PostSearchRawStream::new(client, query, page),
+        }
+    }
+
+    /// Drops the posts hidden by the given blacklist entries. See [`Blacklist`] for the syntax.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut post_stream = client
+    ///     .post_search(&["fluffy"][..])
+    ///     .filter_blacklist(&["feral", "-fox"])
+    ///     .take(3);
+    ///
+    /// while let Some(post) = post_stream.next().await {
+    ///     println!("Post #{}", post?.id);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn filter_blacklist<T: AsRef<str>>(
+        self,
+        blacklist: &[T],
+    ) -> impl Stream<Item = Rs621Result<Post>> + 'a {
+        let blacklist = Blacklist::new(blacklist);
+        self.try_filter(move |post| future::ready(!blacklist.is_blacklisted(post)))
+    }
+}
+
+impl<'a> Stream for PostSearchStream<'a> {
+    type Item = Rs621Result<Post>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Rs621Result<Post>>> {
+        // posts are deserialized one by one as they're streamed, so a malformed post is only an
+        // error for itself
+        let raw = &mut self.get_mut().raw;
+        let client = raw.client;
+
+        raw.poll_next_unpin(cx).map(|res| {
+            res.map(|raw| {
+                raw.and_then(|post| {
+                    serde_json::from_value(post).map_err(|e| Error::Serial(format!("{}", e)))
+                })
+                .and_then(|post| client.check_post(post))
+            })
+        })
+    }
+}
+
 /// Iterator returning posts from a search query.
 #[derive(Derivative)]
 #[derivative(Debug)]
@@ -415,13 +1398,18 @@ where
 {
     client: &'a Client,
     ids: I,
+    /// IDs of the current batch that weren't streamed yet.
+    batch: Vec<u64>,
+    /// IDs of the batches that couldn't be fetched.
+    failed: Vec<u64>,
 
     query_url: Option<String>,
 
     #[derivative(Debug = "ignore")]
     query_future: Option<Pin<QueryFuture>>,
 
-    chunk: Vec<Rs621Result<Post>>,
+    /// Results of the current batch along with the ID of their post, if known, last one first.
+    chunk: Vec<(Option<u64>, Rs621Result<Post>)>,
 }
 
 impl<'a, I, T> PostStream<'a, I, T>
@@ -433,11 +1421,58 @@ where
         PostStream {
             client,
             ids,
+            batch: Vec::new(),
+            failed: Vec::new(),
             query_url: None,
             query_future: None,
             chunk: Vec::new(),
         }
     }
+
+    /// Consumes the stream and returns the IDs of the posts it didn't yield yet, so that the
+    /// fetch can be persisted and resumed later with [`Client::get_posts`]. This includes the
+    /// IDs of the requests that failed, and those of the request in flight if there's one. IDs
+    /// the server already reported as not found are left out.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// let ids: Vec<u64> = (1..=1000).collect();
+    ///
+    /// let mut post_stream = client.get_posts(ids);
+    ///
+    /// for _ in 0..150 {
+    ///     if let Some(post) = post_stream.next().await {
+    ///         println!("Post #{}", post?.id);
+    ///     }
+    /// }
+    ///
+    /// let remaining = post_stream.into_remaining();
+    /// # Ok(()) }
+    /// ```
+    pub fn into_remaining(self) -> Vec<u64> {
+        let mut remaining = self.failed;
+        remaining.extend(self.batch);
+        remaining.extend(self.ids.map(|id| *id.borrow()));
+        remaining
+    }
+
+    /// Drops the posts hidden by the given blacklist entries. See [`Blacklist`] for the syntax.
+    pub fn filter_blacklist<U: AsRef<str>>(
+        self,
+        blacklist: &[U],
+    ) -> impl Stream<Item = Rs621Result<Post>> + 'a
+    where
+        I: 'a,
+        T: 'a,
+    {
+        let blacklist = Blacklist::new(blacklist);
+        self.try_filter(move |post| future::ready(!blacklist.is_blacklisted(post)))
+    }
 }
 
 impl<'a, I, T> Stream for PostStream<'a, I, T>
@@ -469,20 +1504,32 @@ where
                                 // put everything in the chunk
                                 this.chunk =
                                     match serde_json::from_value::<PostListApiResponse>(body) {
-                                        Ok(res) => res
-                                            .posts
-                                            .into_iter()
-                                            .rev()
-                                            .map(|post| Ok(post))
-                                            .collect(),
-                                        Err(e) => vec![Err(Error::Serial(format!("{}", e)))],
+                                        Ok(res) => {
+                                            // IDs without a post don't exist, forget them
+                                            this.batch = res.posts.iter().map(|p| p.id).collect();
+
+                                            res.posts
+                                                .into_iter()
+                                                .rev()
+                                                .map(|post| {
+                                                    (Some(post.id), this.client.check_post(post))
+                                                })
+                                                .collect()
+                                        }
+                                        Err(e) => {
+                                            this.failed.append(&mut this.batch);
+                                            vec![(None, Err(Error::Serial(format!("{}", e))))]
+                                        }
                                     };
 
                                 QueryPollRes::NotFetching
                             }
 
                             // if there was an error, stream it
-                            Err(e) => QueryPollRes::Err(e),
+                            Err(e) => {
+                                this.failed.append(&mut this.batch);
+                                QueryPollRes::Err(e)
+                            }
                         }
                     }
 
@@ -497,28 +1544,35 @@ where
                 QueryPollRes::Pending => return Poll::Pending,
                 QueryPollRes::NotFetching if !this.chunk.is_empty() => {
                     // get a post
-                    let post = this.chunk.pop().unwrap();
+                    let (id, post) = this.chunk.pop().unwrap();
+
+                    // invalid posts are streamed as errors, they're done with too
+                    if let Some(id) = id {
+                        this.batch.retain(|&batch_id| batch_id != id);
+                    }
 
                     // stream the post
                     return Poll::Ready(Some(post));
                 }
                 QueryPollRes::NotFetching => {
                     // we need to load a new chunk of posts
-                    let id_list = this.ids.by_ref().take(100).map(|x| *x.borrow()).join(",");
+                    this.batch.clear();
+                    this.batch
+                        .extend(this.ids.by_ref().take(100).map(|x| *x.borrow()));
 
-                    if id_list.is_empty() {
+                    if this.batch.is_empty() {
                         // the stream ended
                         return Poll::Ready(None);
                     }
 
+                    let id_list = this.batch.iter().join(",");
+
                     let url = format!("/posts.json?tags=id%3A{}", id_list);
                     this.query_url = Some(url);
 
                     // get the JSON
-                    this.query_future = Some(Box::pin(
-                        this.client
-                            .get_json_endpoint(this.query_url.as_ref().unwrap()),
-                    ));
+                    this.query_future =
+                        Some(this.client.get_json_page(this.query_url.as_ref().unwrap()));
                 }
             }
         }
@@ -526,33 +1580,126 @@ where
 }
 
 impl Client {
-    /// Returns posts with the given IDs. Note that the order is NOT preserved!
+    /// Returns `post`, or an error if it's invalid and the client validates responses.
+    fn check_post(&self, post: Post) -> Rs621Result<Post> {
+        if self.validate_responses {
+            post.check_invariants()?;
+        }
+
+        Ok(post)
+    }
+
+    /// Returns the post with the given ID.
     ///
     /// ```no_run
     /// # use rs621::client::Client;
-    /// use futures::prelude::*;
-    ///
     /// # #[tokio::main]
     /// # async fn main() -> rs621::error::Result<()> {
     /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
-    /// let mut post_stream = client.get_posts(&[8595, 535, 2105, 1470]);
     ///
-    /// while let Some(post) = post_stream.next().await {
-    ///     println!("Post #{}", post?.id);
-    /// }
+    /// let post = client.get_post(8595).await?;
+    /// assert_eq!(post.id, 8595);
     /// # Ok(()) }
     /// ```
-    pub fn get_posts<'a, I, J, T>(&'a self, ids: I) -> PostStream<'a, J, T>
-    where
-        T: Borrow<u64> + Unpin,
-        J: Iterator<Item = T> + Unpin,
-        I: IntoIterator<Item = T, IntoIter = J> + Unpin,
-    {
-        PostStream::new(self, ids.into_iter())
+    pub async fn get_post(&self, id: u64) -> Rs621Result<Post> {
+        let body = self
+            .get_json_endpoint(&format!("/posts/{}.json", id))
+            .await?;
+
+        serde_json::from_value::<PostShowApiResponse>(body)
+            .map_err(|e| Error::Serial(format!("{}", e)))
+            .and_then(|res| self.check_post(res.post))
+    }
+
+    /// Follows the parents of `post` up to the topmost one, and returns it. Returns a clone of
+    /// `post` if it has no parent.
+    ///
+    /// Fails with [`Error::ParentCycle`] if the chain of parents loops, or is deeper than 100
+    /// posts.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let post = client.get_post(8595).await?;
+    /// let root = client.root_post(&post).await?;
+    /// assert_eq!(root.relationships.parent_id, None);
+    /// # Ok(()) }
+    /// ```
+    pub async fn root_post(&self, post: &Post) -> Rs621Result<Post> {
+        let mut visited = HashSet::new();
+        visited.insert(post.id);
+
+        let mut current = post.clone();
+
+        while let Some(parent_id) = current.relationships.parent_id {
+            if !visited.insert(parent_id) || visited.len() > ROOT_POST_MAX_DEPTH {
+                return Err(Error::ParentCycle { id: post.id });
+            }
+
+            current = self.get_post(parent_id).await?;
+        }
+
+        Ok(current)
+    }
+
+    /// Returns the ID of the newest post on the site. Useful as a starting point to walk the
+    /// posts backward, e.g. with `id:<N` searches.
+    ///
+    /// Fails with [`Error::Serial`] if the site has no posts at all.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let latest = client.latest_post_id().await?;
+    /// println!("There are no posts newer than #{}", latest);
+    /// # Ok(()) }
+    /// ```
+    pub async fn latest_post_id(&self) -> Rs621Result<u64> {
+        let body = self.get_json_endpoint("/posts.json?limit=1").await?;
+
+        body["posts"][0]["id"]
+            .as_u64()
+            .ok_or_else(|| Error::Serial("the response contains no post".into()))
+    }
+
+    /// Returns posts with the given IDs. Note that the order is NOT preserved!
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// let mut post_stream = client.get_posts(&[8595, 535, 2105, 1470]);
+    ///
+    /// while let Some(post) = post_stream.next().await {
+    ///     println!("Post #{}", post?.id);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn get_posts<'a, I, J, T>(&'a self, ids: I) -> PostStream<'a, J, T>
+    where
+        T: Borrow<u64> + Unpin,
+        J: Iterator<Item = T> + Unpin,
+        I: IntoIterator<Item = T, IntoIter = J> + Unpin,
+    {
+        PostStream::new(self, ids.into_iter())
     }
 
     /// Returns a Stream over all the posts matching the search query.
     ///
+    /// Results are fetched relative to the last post received, except for ordered queries which
+    /// are fetched page by page. The server doesn't serve pages past the 750th, so those stop
+    /// there with an error, unless they're ordered by descending ID (`order:id_desc`): these
+    /// switch to fetching relative to the last post received at that point, and go on.
+    ///
     /// ```no_run
     /// # use rs621::{client::Client, post::PostRating};
     /// use futures::prelude::*;
@@ -603,181 +1750,1565 @@ impl Client {
         PostSearchStream::new(self, tags, page)
     }
 
-    /// Mark a [`Post`] (identified by `id`) as particularly liked.
+    /// Runs several searches at once and merges their results, e.g. to make a feed out of several
+    /// interests.
+    ///
+    /// The results are interleaved in a round-robin fashion, so that no search dominates the
+    /// others: the first post of the first query, then the first post of the second query, and so
+    /// on. A post that was already yielded by another query is skipped. Once a search runs out of
+    /// posts (or fails), the others keep going.
     ///
     /// ```no_run
-    /// # use {
-    /// #     rs621::client::Client,
-    /// #     futures::prelude::*,
-    /// # };
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    /// use rs621::post::Query;
+    ///
     /// # #[tokio::main]
     /// # async fn main() -> rs621::error::Result<()> {
     /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
     ///
-    /// let post = client.post_favorite(1234).await?;
-    /// assert_eq!(post.id, 1234);
+    /// let mut feed = client
+    ///     .post_search_multi(vec![Query::from("fluffy"), Query::from("scalie")])
+    ///     .take(50);
+    ///
+    /// while let Some(post) = feed.next().await {
+    ///     println!("Post #{}", post?.id);
+    /// }
     /// # Ok(()) }
     /// ```
-    pub async fn post_favorite(&self, id: u64) -> Result<Post, Error> {
-        #[derive(Serialize)]
-        struct Form {
-            post_id: u64,
-        }
+    pub fn post_search_multi(
+        &self,
+        queries: Vec<Query>,
+    ) -> impl Stream<Item = Rs621Result<Post>> + '_ {
+        dedup_posts(Interleave::new(
+            queries
+                .into_iter()
+                .map(|query| self.post_search(query))
+                .collect(),
+        ))
+    }
 
-        let response = self
-            .post_form("/favorites.json", &Form { post_id: id })
+    /// Returns a Stream over the posts matching the search query that are newer than
+    /// `stop_at_id`, newest first. The stream ends at the first post whose ID is `stop_at_id` or
+    /// lower, without requesting any further page. Useful to fetch what was posted since the last
+    /// sync.
+    ///
+    /// This relies on the posts coming by decreasing ID, so the query shouldn't have an `order:`
+    /// tag.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let new_posts: Vec<_> = client
+    ///     .post_search_until(&["fluffy"][..], 4000000)
+    ///     .try_collect()
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn post_search_until<'a, T: Into<Query>>(
+        &'a self,
+        tags: T,
+        stop_at_id: u64,
+    ) -> impl Stream<Item = Rs621Result<Post>> + 'a {
+        self.post_search(tags)
+            .take_while(move |res| future::ready(!matches!(res, Ok(post) if post.id <= stop_at_id)))
+    }
+
+    /// Returns up to `limit` posts matching `include` but not `exclude`, for the cases the tag
+    /// syntax can't express. Posts are returned in the order of the `include` search.
+    ///
+    /// The IDs of all the posts matching `exclude` are fetched first (only the IDs are requested),
+    /// so it should be the narrower of the two searches.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let posts = client
+    ///     .search_difference(&["comic"][..], &["pool:1234"][..], 100)
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn search_difference<T, U>(
+        &self,
+        include: T,
+        exclude: U,
+        limit: usize,
+    ) -> Rs621Result<Vec<Post>>
+    where
+        T: Into<Query>,
+        U: Into<Query>,
+    {
+        let excluded: HashSet<u64> = self.post_search_ids(exclude).try_collect().await?;
+
+        self.post_search(include)
+            .try_filter(|post| future::ready(!excluded.contains(&post.id)))
+            .take(limit)
+            .try_collect()
+            .await
+    }
+
+    /// Returns the number of posts matching the search query, without fetching them.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let count = client.count_posts(&["fluffy", "rating:s"][..]).await?;
+    /// println!("{} posts", count);
+    /// # Ok(()) }
+    /// ```
+    pub async fn count_posts<T: Into<Query>>(&self, tags: T) -> Rs621Result<u64> {
+        let query = tags.into();
+        query.validate()?;
+
+        let body = self
+            .get_json_endpoint(&format!(
+                "/counts/posts.json?tags={}",
+                query.url_encoded_tags
+            ))
             .await?;
 
-        let value = response
-            .as_object()
-            .and_then(|o| o.get("post"))
-            .cloned()
-            .ok_or_else(|| Error::Serial("unexpected response".into()))?;
+        body["counts"]["posts"]
+            .as_u64()
+            .ok_or_else(|| Error::Serial("missing field `counts.posts`".into()))
+    }
 
-        serde_json::from_value(value).map_err(|e| Error::Serial(format!("{}", e)))
+    /// Returns the first page of results of a search, unless it didn't change since the response
+    /// that had the given ETag, in which case `None` is returned. Meant for polling the same
+    /// search often without downloading the same results every time.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// let page = client.post_search_if_changed("fluffy", None).await?.unwrap();
+    ///
+    /// // later...
+    /// if let Some(page) = client
+    ///     .post_search_if_changed("fluffy", page.etag.as_deref())
+    ///     .await?
+    /// {
+    ///     println!("The results changed: {} posts", page.data.len());
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn post_search_if_changed<T: Into<Query>>(
+        &self,
+        tags: T,
+        etag: Option<&str>,
+    ) -> Rs621Result<Option<ETagged<Vec<Post>>>> {
+        let query = tags.into();
+        query.validate()?;
+
+        let endpoint = format!(
+            "/posts.json?limit={}&tags={}",
+            ITER_CHUNK_SIZE, query.url_encoded_tags
+        );
+
+        let tagged = match self.get_json_endpoint_if_changed(&endpoint, etag).await? {
+            Some(tagged) => tagged,
+            None => return Ok(None),
+        };
+
+        Ok(Some(ETagged {
+            data: self.parse_post_list(tagged.data)?,
+            etag: tagged.etag,
+        }))
     }
 
-    /// Mark a [`Post`] (identified by `id`) as no longer particularly liked.
+    /// Returns the first `n` posts matching the search query, in a single request. Prefer this
+    /// over taking a few posts from [`Client::post_search`], which always requests whole pages
+    /// of 320 posts. `n` is capped to 320.
     ///
     /// ```no_run
-    /// # use {
-    /// #     rs621::client::Client,
-    /// #     futures::prelude::*,
-    /// # };
+    /// # use rs621::client::Client;
     /// # #[tokio::main]
     /// # async fn main() -> rs621::error::Result<()> {
     /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
     ///
-    /// client.post_unfavorite(1234).await?;
+    /// let top = client.post_search_n(&["fluffy", "order:score"][..], 3).await?;
+    /// assert!(top.len() <= 3);
     /// # Ok(()) }
     /// ```
-    pub async fn post_unfavorite(&self, id: u64) -> Result<(), Error> {
-        self.delete(&format!("/favorites/{id}.json")).await?;
-        Ok(())
+    pub async fn post_search_n<T: Into<Query>>(&self, tags: T, n: usize) -> Rs621Result<Vec<Post>> {
+        let query = tags.into();
+        query.validate()?;
+
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+
+        let body = self
+            .get_json_endpoint(&format!(
+                "/posts.json?limit={}&tags={}",
+                (n as u64).min(ITER_CHUNK_SIZE),
+                query.url_encoded_tags
+            ))
+            .await?;
+
+        self.parse_post_list(body)
     }
 
-    /// Vote a [`Post`] (identified by `id`) up or down.
+    /// Returns the posts right after and right before `post_id` among the posts matching the
+    /// search query, in the default newest first order: the next post is the newest one older than
+    /// `post_id`, and the previous post the oldest one newer than it. Either is `None` at the ends
+    /// of the results. Useful to step through search results without fetching them all.
     ///
-    /// Use [`VoteDir::Toggle`] to clear an existing vote.
+    /// `post_id` itself doesn't have to match the query. Any `order:` tag in the query is
+    /// replaced, since neighbors are looked up by ID. This makes two requests.
     ///
     /// ```no_run
-    /// # use {
-    /// #     rs621::client::Client,
-    /// #     rs621::post::{VoteDir, VoteMethod},
-    /// #     futures::prelude::*,
-    /// # };
+    /// # use rs621::client::Client;
     /// # #[tokio::main]
     /// # async fn main() -> rs621::error::Result<()> {
     /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
     ///
-    /// let scores = client.post_vote(1234, VoteMethod::Set, VoteDir::Up).await?;
-    /// assert_eq!(scores.our_score, Some(VoteDir::Up));
+    /// let (next, previous) = client.post_neighbors("fluffy", 8595).await?;
+    ///
+    /// if let Some(next) = next {
+    ///     println!("Next: #{}", next.id);
+    /// }
     /// # Ok(()) }
     /// ```
-    pub async fn post_vote(
+    pub async fn post_neighbors<T: Into<Query>>(
         &self,
-        id: u64,
-        method: VoteMethod,
-        dir: VoteDir,
-    ) -> Result<VoteScore, Error> {
-        #[derive(Serialize)]
-        struct Form {
-            score: i8,
+        tags: T,
+        post_id: u64,
+    ) -> Rs621Result<(Option<Post>, Option<Post>)> {
+        let query = tags.into();
 
-            #[serde(skip_serializing_if = "is_false")]
-            no_unvote: bool,
+        let next = query
+            .clone()
+            .with_tag(format!("id:<{}", post_id))
+            .order(PostOrder::Id);
+        let previous = query
+            .with_tag(format!("id:>{}", post_id))
+            .order_asc(PostOrder::Id);
+
+        let (next, previous) =
+            future::try_join(self.post_search_n(next, 1), self.post_search_n(previous, 1)).await?;
+
+        Ok((next.into_iter().next(), previous.into_iter().next()))
+    }
+
+    fn parse_post_list(&self, body: serde_json::Value) -> Rs621Result<Vec<Post>> {
+        serde_json::from_value::<PostListApiResponse>(body)
+            .map_err(|e| Error::Serial(format!("{}", e)))?
+            .posts
+            .into_iter()
+            .map(|post| self.check_post(post))
+            .collect()
+    }
+
+    /// Returns the first `max_items` posts matching the search query, from the cache set up with
+    /// [`ClientBuilder::search_cache`](crate::client::ClientBuilder::search_cache) if the same
+    /// search was made recently enough. Without a cache, this is the same as collecting
+    /// [`Client::post_search`].
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::builder("https://e926.net", "MyProject/1.0 (by username on e621)")
+    ///     .search_cache(100, Duration::from_secs(60))
+    ///     .build()?;
+    ///
+    /// let posts = client.cached_post_search(&["fluffy"][..], 20).await?;
+    ///
+    /// // sends no request
+    /// let same_posts = client.cached_post_search(&["fluffy"][..], 20).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn cached_post_search<T: Into<Query>>(
+        &self,
+        tags: T,
+        max_items: usize,
+    ) -> Rs621Result<Vec<Post>> {
+        let query = tags.into();
+        // logged in users may see other posts, so results are only shared with the same user
+        let key = format!(
+            "{}&{}&{}",
+            max_items,
+            query.url_encoded_tags,
+            self.username().unwrap_or_default()
+        );
+
+        if let Some(posts) = self.search_cache.as_ref().and_then(|cache| cache.get(&key)) {
+            return Ok(posts);
         }
 
-        let response = self
-            .post_form(
-                &format!("/posts/{id}/votes.json"),
-                &Form {
-                    score: i8::from(dir),
-                    no_unvote: method == VoteMethod::Set,
-                },
+        let posts: Vec<Post> = self
+            .post_search(query)
+            .take(max_items)
+            .try_collect()
+            .await?;
+
+        if let Some(ref cache) = self.search_cache {
+            cache.insert(key, posts.clone());
+        }
+
+        Ok(posts)
+    }
+
+    /// Collects up to `limit` posts matching the search query, stopping at the first error. Unlike
+    /// `try_collect`, the posts fetched before the error aren't lost: they're returned along with
+    /// it.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let (posts, error) = client
+    ///     .post_search_collect_partial(&["fluffy"][..], 1000)
+    ///     .await;
+    ///
+    /// println!("Got {} posts", posts.len());
+    ///
+    /// if let Some(e) = error {
+    ///     println!("The search failed midway: {}", e);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn post_search_collect_partial<T: Into<Query>>(
+        &self,
+        tags: T,
+        limit: usize,
+    ) -> (Vec<Post>, Option<Error>) {
+        let mut stream = self.post_search(tags).take(limit);
+        let mut posts = Vec::new();
+
+        while let Some(post) = stream.next().await {
+            match post {
+                Ok(post) => posts.push(post),
+                Err(e) => return (posts, Some(e)),
+            }
+        }
+
+        (posts, None)
+    }
+
+    /// Returns `n` distinct posts picked at random among the ones matching the search query. Any
+    /// `order:` tag in the query is replaced by `order:random`.
+    ///
+    /// Random pages overlap, so more requests are made until `n` distinct posts are found, up to
+    /// 10 of them. Fewer posts are returned if not enough posts match the query, or if they
+    /// couldn't all be found in time.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let posts = client.random_posts(&["fluffy"][..], 50).await?;
+    /// assert!(posts.len() <= 50);
+    /// # Ok(()) }
+    /// ```
+    pub async fn random_posts<T: Into<Query>>(&self, tags: T, n: usize) -> Rs621Result<Vec<Post>> {
+        let query = tags.into().order(PostOrder::Random);
+        query.validate()?;
+
+        let mut seen = HashSet::new();
+        let mut posts = Vec::new();
+
+        for _ in 0..RANDOM_POSTS_MAX_ATTEMPTS {
+            if posts.len() >= n {
+                break;
+            }
+
+            let limit = ((n - posts.len()) as u64).min(ITER_CHUNK_SIZE);
+            let body = self
+                .get_json_endpoint(&format!(
+                    "/posts.json?limit={}&tags={}",
+                    limit, query.url_encoded_tags
+                ))
+                .await?;
+
+            let page = serde_json::from_value::<PostListApiResponse>(body)
+                .map_err(|e| Error::Serial(format!("{}", e)))?
+                .posts;
+
+            // a short page holds every post matching the query, more requests won't find others
+            let exhausted = (page.len() as u64) < limit;
+
+            for post in page {
+                if posts.len() < n && seen.insert(post.id) {
+                    posts.push(self.check_post(post)?);
+                }
+            }
+
+            if exhausted {
+                break;
+            }
+        }
+
+        Ok(posts)
+    }
+
+    /// Returns a Stream over all the posts matching the search query, like
+    /// [`Client::post_search`], but as raw JSON values. Useful to deserialize them into your own
+    /// type.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut post_stream = client.post_search_raw(&["fluffy"][..]).take(20);
+    ///
+    /// while let Some(post) = post_stream.next().await {
+    ///     println!("Post #{}", post?["id"]);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn post_search_raw<'a, T: Into<Query>>(&'a self, tags: T) -> PostSearchRawStream<'a> {
+        PostSearchRawStream::new(self, tags, SearchPage::Page(1))
+    }
+
+    /// Like [`Client::post_search_raw`], with additional query parameters appended to every
+    /// request. An escape hatch for the parameters this crate doesn't model.
+    ///
+    /// For instance, `only` makes the server send only the given fields of each post, which cuts
+    /// the size of responses down a lot. The posts wouldn't deserialize into [`Post`]s anymore,
+    /// hence the raw JSON values. Keep `id` in the fields, it's needed to fetch the next pages.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut post_stream = client
+    ///     .post_search_with_params(&["fluffy"][..], &[("only", "id,file")])
+    ///     .take(20);
+    ///
+    /// while let Some(post) = post_stream.next().await {
+    ///     let post = post?;
+    ///     println!("Post #{}: {}", post["id"], post["file"]["url"]);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn post_search_with_params<'a, T: Into<Query>>(
+        &'a self,
+        tags: T,
+        extra: &[(&str, &str)],
+    ) -> PostSearchRawStream<'a> {
+        PostSearchRawStream::new(self, tags, SearchPage::Page(1)).with_params(extra)
+    }
+
+    /// Like [`Client::post_search`], but only yields the IDs of the posts. Only the IDs are
+    /// requested from the server, which makes responses much smaller.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let ids: Vec<u64> = client
+    ///     .post_search_ids(&["fluffy"][..])
+    ///     .take(1000)
+    ///     .try_collect()
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn post_search_ids<'a, T: Into<Query>>(
+        &'a self,
+        tags: T,
+    ) -> impl Stream<Item = Rs621Result<u64>> + 'a {
+        #[derive(Deserialize)]
+        struct PostId {
+            id: u64,
+        }
+
+        self.post_search_with_params(tags, &[("only", "id")])
+            .map(|res| {
+                res.and_then(|post| {
+                    serde_json::from_value::<PostId>(post)
+                        .map_err(|e| Error::Serial(format!("{}", e)))
+                })
+            })
+            .map_ok(|post| post.id)
+    }
+
+    /// Like [`Client::post_search`], but only yields the MD5 hashes of the posts' files. Only
+    /// the IDs and files are requested from the server, which makes responses much smaller.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let md5s: Vec<String> = client
+    ///     .post_search_md5s(&["fluffy"][..])
+    ///     .take(1000)
+    ///     .try_collect()
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn post_search_md5s<'a, T: Into<Query>>(
+        &'a self,
+        tags: T,
+    ) -> impl Stream<Item = Rs621Result<String>> + 'a {
+        #[derive(Deserialize)]
+        struct PostMd5File {
+            md5: String,
+        }
+
+        #[derive(Deserialize)]
+        struct PostMd5 {
+            file: PostMd5File,
+        }
+
+        // the ID is needed to fetch the next pages
+        self.post_search_with_params(tags, &[("only", "id,file")])
+            .map(|res| {
+                res.and_then(|post| {
+                    serde_json::from_value::<PostMd5>(post)
+                        .map_err(|e| Error::Serial(format!("{}", e)))
+                })
+            })
+            .map_ok(|post| post.file.md5)
+    }
+
+    /// Like [`Client::post_search`], but only yields [`PostThumbnail`]s. The posts are never
+    /// deserialized in full, which saves memory when keeping lots of them around, e.g. in a grid.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let thumbnails: Vec<_> = client
+    ///     .post_search_thumbnails(&["fluffy"][..])
+    ///     .take(100)
+    ///     .try_collect()
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn post_search_thumbnails<'a, T: Into<Query>>(
+        &'a self,
+        tags: T,
+    ) -> impl Stream<Item = Rs621Result<PostThumbnail>> + 'a {
+        self.post_search_raw(tags).map(|res| {
+            res.and_then(|post| {
+                serde_json::from_value(post).map_err(|e| Error::Serial(format!("{}", e)))
+            })
+        })
+    }
+
+    /// Mark a [`Post`] (identified by `id`) as particularly liked. Fails with
+    /// [`Error::Unauthorized`] without sending anything if the client isn't logged in.
+    ///
+    /// ```no_run
+    /// # use {
+    /// #     rs621::client::Client,
+    /// #     futures::prelude::*,
+    /// # };
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let mut client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// client.login(("username", "api_key"));
+    ///
+    /// let post = client.post_favorite(1234).await?;
+    /// assert_eq!(post.id, 1234);
+    /// # Ok(()) }
+    /// ```
+    pub async fn post_favorite(&self, id: u64) -> Result<Post, Error> {
+        self.require_login()?;
+
+        #[derive(Serialize)]
+        struct Form {
+            post_id: u64,
+        }
+
+        let response = self
+            .post_form("/favorites.json", &Form { post_id: id })
+            .await?;
+
+        let value = response
+            .as_object()
+            .and_then(|o| o.get("post"))
+            .cloned()
+            .ok_or_else(|| Error::Serial("unexpected response".into()))?;
+
+        serde_json::from_value(value).map_err(|e| Error::Serial(format!("{}", e)))
+    }
+
+    /// Favorites each of the posts in `ids`, in order, and yields the result for every one of
+    /// them, so that failures can be reported or retried individually.
+    ///
+    /// The API has no endpoint to favorite several posts at once, so this sends one request per
+    /// post, each of them going through the rate limit like [`Client::post_favorite`].
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let mut client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// client.login(("username", "api_key"));
+    ///
+    /// let mut results = client.favorite_posts(&[8595, 535, 2105, 1470]);
+    ///
+    /// while let Some((id, result)) = results.next().await {
+    ///     match result {
+    ///         Ok(()) => println!("- #{}: favorited", id),
+    ///         Err(e) => println!("- #{}: couldn't favorite: {}", id, e),
+    ///     }
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn favorite_posts<'a>(
+        &'a self,
+        ids: &[u64],
+    ) -> impl Stream<Item = (u64, Rs621Result<()>)> + Unpin + 'a {
+        Box::pin(
+            stream::iter(ids.to_vec())
+                .then(move |id| async move { (id, self.post_favorite(id).await.map(|_| ())) }),
+        )
+    }
+
+    /// Mark a [`Post`] (identified by `id`) as no longer particularly liked. Fails with
+    /// [`Error::Unauthorized`] without sending anything if the client isn't logged in.
+    ///
+    /// ```no_run
+    /// # use {
+    /// #     rs621::client::Client,
+    /// #     futures::prelude::*,
+    /// # };
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let mut client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// client.login(("username", "api_key"));
+    ///
+    /// client.post_unfavorite(1234).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn post_unfavorite(&self, id: u64) -> Result<(), Error> {
+        self.require_login()?;
+        self.delete(&format!("/favorites/{id}.json")).await?;
+        Ok(())
+    }
+
+    /// Vote a [`Post`] (identified by `id`) up or down.
+    ///
+    /// Use [`VoteDir::Toggle`] to clear an existing vote. Fails with [`Error::Unauthorized`] without
+    /// sending anything if the client isn't logged in.
+    ///
+    /// ```no_run
+    /// # use {
+    /// #     rs621::client::Client,
+    /// #     rs621::post::{VoteDir, VoteMethod},
+    /// #     futures::prelude::*,
+    /// # };
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let mut client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// client.login(("username", "api_key"));
+    ///
+    /// let scores = client.post_vote(1234, VoteMethod::Set, VoteDir::Up).await?;
+    /// assert_eq!(scores.our_score, Some(VoteDir::Up));
+    /// # Ok(()) }
+    /// ```
+    pub async fn post_vote(
+        &self,
+        id: u64,
+        method: VoteMethod,
+        dir: VoteDir,
+    ) -> Result<VoteScore, Error> {
+        self.require_login()?;
+
+        #[derive(Serialize)]
+        struct Form {
+            score: i8,
+
+            #[serde(skip_serializing_if = "is_false")]
+            no_unvote: bool,
+        }
+
+        let response = self
+            .post_form(
+                &format!("/posts/{id}/votes.json"),
+                &Form {
+                    score: i8::from(dir),
+                    no_unvote: method == VoteMethod::Set,
+                },
+            )
+            .await?;
+
+        serde_json::from_value(response).map_err(|e| Error::Serial(format!("{}", e)))
+    }
+
+    /// Delete a [`Post`] (identified by `id`), giving a `reason`. If `move_favorites_to` is set,
+    /// the post's favorites are moved to the given post, which becomes its parent.
+    ///
+    /// This requires moderator privileges: the server's 403 response is reported as
+    /// [`Error::Unauthorized`], as is the absence of login information (without sending anything).
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let mut client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// client.login(("username", "api_key"));
+    ///
+    /// client.delete_post(1234, "Duplicate of #1233", Some(1233)).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn delete_post(
+        &self,
+        id: u64,
+        reason: &str,
+        move_favorites_to: Option<u64>,
+    ) -> Result<(), Error> {
+        #[derive(Serialize)]
+        struct Form<'a> {
+            reason: &'a str,
+
+            #[serde(skip_serializing_if = "is_false")]
+            move_favorites: bool,
+
+            #[serde(skip_serializing_if = "Option::is_none")]
+            parent_id: Option<u64>,
+        }
+
+        self.require_login()?;
+
+        let form = Form {
+            reason,
+            move_favorites: move_favorites_to.is_some(),
+            parent_id: move_favorites_to,
+        };
+
+        match self
+            .post_response(&format!("/moderator/post/posts/{id}/delete.json"), &form)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(Error::Http { code: 403, .. }) => Err(Error::Unauthorized),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+fn is_false(b: &bool) -> bool {
+    !b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn post_vote_up_set() {
+        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        client.login(("foo", "bar"));
+
+        let body = r#"{"score":41,"up":44,"down":-3,"our_score":1}"#;
+
+        let _m = mock(
+            "POST",
+            Matcher::Exact("/posts/1234/votes.json?login=foo&api_key=bar".into()),
+        )
+        .match_body("score=1&no_unvote=true")
+        .with_body(body)
+        .create();
+
+        let expected = serde_json::from_str::<VoteScore>(body).unwrap();
+
+        assert_eq!(
+            client
+                .post_vote(1234, VoteMethod::Set, VoteDir::Up)
+                .await
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[tokio::test]
+    async fn post_favorite() {
+        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        client.login(("foo", "bar"));
+
+        let _m = mock(
+            "POST",
+            Matcher::Exact("/favorites.json?login=foo&api_key=bar".into()),
+        )
+        .match_body("post_id=3758515")
+        .with_body(include_str!("mocked/favorite.json"))
+        .create();
+
+        let expected =
+            serde_json::from_str::<serde_json::Value>(include_str!("mocked/favorite.json"))
+                .unwrap()
+                .as_object()
+                .unwrap()
+                .get("post")
+                .cloned()
+                .unwrap();
+
+        assert_eq!(
+            client.post_favorite(3758515).await.unwrap(),
+            serde_json::from_value(expected).unwrap(),
+        );
+    }
+
+    #[tokio::test]
+    async fn favorite_posts() {
+        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        client.login(("foo", "bar"));
+
+        let _m = [
+            mock(
+                "POST",
+                Matcher::Exact("/favorites.json?login=foo&api_key=bar".into()),
+            )
+            .match_body("post_id=3758515")
+            .with_body(include_str!("mocked/favorite.json"))
+            .create(),
+            mock(
+                "POST",
+                Matcher::Exact("/favorites.json?login=foo&api_key=bar".into()),
+            )
+            .match_body("post_id=1")
+            .with_status(404)
+            .create(),
+        ];
+
+        let results = client
+            .favorite_posts(&[3758515, 1])
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0], (3758515, Ok(())));
+        assert!(matches!(
+            results[1],
+            (1, Err(Error::Http { code: 404, .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn post_unfavorite() {
+        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        client.login(("foo", "bar"));
+
+        let _m = mock(
+            "POST",
+            Matcher::Exact("/favorites/3758515.json?login=foo&api_key=bar".into()),
+        )
+        .match_body("_method=delete")
+        .create();
+
+        client.post_unfavorite(3758515).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn post_writes_require_login() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        assert_eq!(client.post_favorite(1234).await, Err(Error::Unauthorized));
+        assert_eq!(client.post_unfavorite(1234).await, Err(Error::Unauthorized));
+        assert_eq!(
+            client.post_vote(1234, VoteMethod::Set, VoteDir::Up).await,
+            Err(Error::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_post() {
+        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        client.login(("foo", "bar"));
+
+        let _m = mock(
+            "POST",
+            Matcher::Exact("/moderator/post/posts/1234/delete.json?login=foo&api_key=bar".into()),
+        )
+        .match_body("reason=Duplicate+of+%231233&move_favorites=true&parent_id=1233")
+        .create();
+
+        client
+            .delete_post(1234, "Duplicate of #1233", Some(1233))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn delete_post_forbidden() {
+        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        client.login(("foo", "bar"));
+
+        let _m = mock(
+            "POST",
+            Matcher::Exact("/moderator/post/posts/1235/delete.json?login=foo&api_key=bar".into()),
+        )
+        .match_body("reason=Spam")
+        .with_status(403)
+        .with_body(r#"{"success":false,"reason":"Access Denied"}"#)
+        .create();
+
+        assert_eq!(
+            client.delete_post(1235, "Spam", None).await,
+            Err(Error::Unauthorized)
+        );
+    }
+
+    /// Returns post #8595 with the given ID and parent.
+    fn post_with_parent(id: u64, parent_id: Option<u64>) -> serde_json::Value {
+        let mut post: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/id_8595.json")).unwrap();
+        post["post"]["id"] = id.into();
+        post["post"]["relationships"]["parent_id"] = parent_id.into();
+        post
+    }
+
+    #[tokio::test]
+    async fn get_post() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock("GET", "/posts/8595.json")
+            .with_body(include_str!("mocked/id_8595.json"))
+            .create();
+
+        assert_eq!(
+            client.get_post(8595).await,
+            Ok(
+                serde_json::from_str::<PostShowApiResponse>(include_str!("mocked/id_8595.json"))
+                    .unwrap()
+                    .post
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn validate_responses() {
+        let client = Client::builder(&mockito::server_url(), b"rs621/unit_test")
+            .validate_responses(true)
+            .build()
+            .unwrap();
+
+        // the score of #8595 doesn't add up in the fixture (it doesn't always on e621 either)
+        let valid = |id| {
+            let mut post = post_with_parent(id, None);
+            post["post"]["score"]["total"] = 82.into();
+            post
+        };
+
+        let mut bad_score = valid(2001);
+        bad_score["post"]["score"]["total"] = 1000.into();
+
+        let mut no_md5 = valid(2002);
+        no_md5["post"]["file"]["md5"] = "".into();
+
+        let mut no_preview = valid(2003);
+        no_preview["post"]["file"]["url"] = "https://static1.e621.net/data/a.png".into();
+        no_preview["post"]["preview"]["url"] = serde_json::Value::Null;
+
+        let _m = [
+            mock("GET", "/posts/2000.json")
+                .with_body(valid(2000).to_string())
+                .create(),
+            mock("GET", "/posts/2001.json")
+                .with_body(bad_score.to_string())
+                .create(),
+            mock("GET", "/posts/2002.json")
+                .with_body(no_md5.to_string())
+                .create(),
+            mock("GET", "/posts/2003.json")
+                .with_body(no_preview.to_string())
+                .create(),
+        ];
+
+        assert!(client.get_post(2000).await.is_ok());
+
+        for id in 2001..=2003 {
+            assert!(
+                matches!(
+                    client.get_post(id).await,
+                    Err(Error::Invalid { post_id, .. }) if post_id == id
+                ),
+                "post #{} should be invalid",
+                id
+            );
+        }
+
+        // validation is disabled by default
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        assert!(client.get_post(2001).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn root_post() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = [
+            mock("GET", "/posts/102.json")
+                .with_body(post_with_parent(102, Some(101)).to_string())
+                .create(),
+            mock("GET", "/posts/101.json")
+                .with_body(post_with_parent(101, None).to_string())
+                .create(),
+        ];
+
+        let post: Post =
+            serde_json::from_value(post_with_parent(103, Some(102))["post"].take()).unwrap();
+        let root = client.root_post(&post).await.unwrap();
+        assert_eq!(root.id, 101);
+
+        // a post without parent is its own root
+        assert_eq!(client.root_post(&root).await, Ok(root.clone()));
+    }
+
+    #[tokio::test]
+    async fn root_post_cycle() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = [
+            mock("GET", "/posts/111.json")
+                .with_body(post_with_parent(111, Some(112)).to_string())
+                .create(),
+            mock("GET", "/posts/112.json")
+                .with_body(post_with_parent(112, Some(111)).to_string())
+                .create(),
+        ];
+
+        let post: Post =
+            serde_json::from_value(post_with_parent(111, Some(112))["post"].take()).unwrap();
+
+        assert_eq!(
+            client.root_post(&post).await,
+            Err(Error::ParentCycle { id: 111 })
+        );
+    }
+
+    #[test]
+    fn post_new() {
+        let mut post = Post::new(8595);
+        post.tags.general.push("fluffy".into());
+
+        assert_eq!(post.id, 8595);
+        assert_eq!(post.tags.iter().collect::<Vec<_>>(), vec!["fluffy"]);
+        assert!(post.pools.is_empty());
+    }
+
+    #[test]
+    fn plain_description() {
+        let mut post = Post::new(1);
+        post.description = "[b]Commission[/b] for [[foo_bar|Foo]]".into();
+
+        assert_eq!(post.plain_description(), "Commission for Foo");
+    }
+
+    #[test]
+    fn url_for_width() {
+        let mut post = Post::new(1);
+        post.preview = PostPreview {
+            width: 150,
+            height: 150,
+            url: Some("preview".into()),
+        };
+        post.sample = Some(PostSample {
+            has: true,
+            width: 850,
+            height: 850,
+            url: Some("sample".into()),
+            alternates: HashMap::new(),
+        });
+        post.file.width = 2000;
+        post.file.url = Some("file".into());
+
+        assert_eq!(post.url_for_width(100), Some("preview"));
+        assert_eq!(post.url_for_width(150), Some("preview"));
+        assert_eq!(post.url_for_width(600), Some("sample"));
+        assert_eq!(post.url_for_width(1000), Some("file"));
+        assert_eq!(post.url_for_width(4000), Some("file"));
+
+        // the sample is wider than the file
+        post.sample.as_mut().unwrap().width = 900;
+        post.file.width = 400;
+        assert_eq!(post.url_for_width(300), Some("file"));
+        assert_eq!(post.url_for_width(500), Some("sample"));
+        assert_eq!(post.url_for_width(1000), Some("sample"));
+
+        // the sample is the file itself
+        post.sample.as_mut().unwrap().has = false;
+        assert_eq!(post.url_for_width(600), Some("file"));
+
+        // deleted post
+        post.file.url = None;
+        assert_eq!(post.url_for_width(4000), Some("preview"));
+        post.preview.url = None;
+        assert_eq!(post.url_for_width(100), None);
+    }
+
+    #[test]
+    fn sample_alternates() {
+        let sample: PostSample = serde_json::from_str(
+            r#"{
+                "has": true,
+                "height": 480,
+                "width": 640,
+                "url": "sample.jpg",
+                "alternates": {
+                    "480p": {
+                        "type": "video",
+                        "height": 480,
+                        "width": 640,
+                        "urls": ["480p.webm", null]
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let alternate = &sample.alternates["480p"];
+        assert_eq!(alternate.kind, "video");
+        assert_eq!(alternate.width, 640);
+        assert_eq!(alternate.urls, vec![Some("480p.webm".into()), None]);
+    }
+
+    #[tokio::test]
+    async fn post_search_if_changed() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = [
+            mock("GET", "/posts.json?limit=320&tags=fluffy")
+                .match_header("if-none-match", Matcher::Missing)
+                .with_header("etag", r#""1234""#)
+                .with_body(include_str!("mocked/id_8595_535_2105_1470.json"))
+                .create(),
+            mock("GET", "/posts.json?limit=320&tags=fluffy")
+                .match_header("if-none-match", r#""1234""#)
+                .with_status(304)
+                .create(),
+        ];
+
+        let page = client
+            .post_search_if_changed("fluffy", None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            page.data.iter().map(|p| p.id).collect::<Vec<_>>(),
+            vec![8595, 2105, 1470, 535]
+        );
+        assert_eq!(page.etag.as_deref(), Some(r#""1234""#));
+
+        assert_eq!(
+            client
+                .post_search_if_changed("fluffy", page.etag.as_deref())
+                .await,
+            Ok(None)
+        );
+    }
+
+    #[tokio::test]
+    async fn post_search_n() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = [
+            mock("GET", "/posts.json?limit=4&tags=fluffy")
+                .with_body(include_str!("mocked/id_8595_535_2105_1470.json"))
+                .create(),
+            mock("GET", "/posts.json?limit=320&tags=fluffy")
+                .with_body(include_str!("mocked/id_8595_535_2105_1470.json"))
+                .create(),
+        ];
+
+        let ids = |posts: Vec<Post>| posts.iter().map(|p| p.id).collect::<Vec<_>>();
+
+        assert_eq!(
+            client.post_search_n("fluffy", 4).await.map(ids),
+            Ok(vec![8595, 2105, 1470, 535])
+        );
+        assert_eq!(
+            client.post_search_n("fluffy", 1000).await.map(ids),
+            Ok(vec![8595, 2105, 1470, 535])
+        );
+        assert_eq!(client.post_search_n("fluffy", 0).await, Ok(Vec::new()));
+    }
+
+    #[tokio::test]
+    async fn post_neighbors() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let post: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/id_8595.json")).unwrap();
+        let page = serde_json::json!({ "posts": [post["post"]] });
+
+        // the query's own order is replaced
+        let _m = [
+            mock(
+                "GET",
+                "/posts.json?limit=1&tags=fluffy%20id%3A%3C2105%20order%3Aid_desc",
+            )
+            .with_body(r#"{"posts":[]}"#)
+            .create(),
+            mock(
+                "GET",
+                "/posts.json?limit=1&tags=fluffy%20id%3A%3E2105%20order%3Aid_asc",
+            )
+            .with_body(page.to_string())
+            .create(),
+        ];
+
+        let (next, previous) = client
+            .post_neighbors(&["fluffy", "order:score"][..], 2105)
+            .await
+            .unwrap();
+
+        assert_eq!(next, None);
+        assert_eq!(previous.map(|p| p.id), Some(8595));
+    }
+
+    #[tokio::test]
+    async fn post_search_multi() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        // `b` has a post of its own, and one that `a` has too
+        let post: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/id_8595.json")).unwrap();
+        let mut other = post["post"].clone();
+        other["id"] = 1.into();
+        let page_b = serde_json::json!({ "posts": [other, post["post"]] });
+
+        let _m = [
+            mock("GET", "/posts.json?limit=320&page=1&tags=a")
+                .with_body(include_str!("mocked/id_8595_535_2105_1470.json"))
+                .create(),
+            mock("GET", "/posts.json?limit=320&page=b535&tags=a")
+                .with_body(r#"{"posts":[]}"#)
+                .create(),
+            mock("GET", "/posts.json?limit=320&page=1&tags=b")
+                .with_body(page_b.to_string())
+                .create(),
+            mock("GET", "/posts.json?limit=320&page=b8595&tags=b")
+                .with_body(r#"{"posts":[]}"#)
+                .create(),
+        ];
+
+        let ids: Vec<u64> = client
+            .post_search_multi(vec![Query::from("a"), Query::from("b")])
+            .map_ok(|post| post.id)
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(ids, vec![8595, 1, 2105, 1470, 535]);
+    }
+
+    #[tokio::test]
+    async fn count_posts() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock("GET", "/counts/posts.json?tags=fluffy%20rating%3As")
+            .with_body(r#"{"counts":{"posts":173902}}"#)
+            .create();
+
+        assert_eq!(
+            client.count_posts(&["fluffy", "rating:s"][..]).await,
+            Ok(173902)
+        );
+    }
+
+    #[tokio::test]
+    async fn latest_post_id() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock("GET", "/posts.json?limit=1")
+            .with_body(r#"{"posts":[{"id":4621377}]}"#)
+            .create();
+
+        assert_eq!(client.latest_post_id().await, Ok(4621377));
+    }
+
+    #[tokio::test]
+    async fn latest_post_id_empty() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock("GET", "/posts.json?limit=1")
+            .with_body(r#"{"posts":[]}"#)
+            .create();
+
+        assert!(matches!(
+            client.latest_post_id().await,
+            Err(Error::Serial(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn cached_search() {
+        let client = Client::builder(&mockito::server_url(), b"rs621/unit_test")
+            .search_cache(1, std::time::Duration::from_secs(60))
+            .build()
+            .unwrap();
+
+        let fetch = |tags: &'static str| {
+            mock(
+                "GET",
+                Matcher::Exact(format!(
+                    "/posts.json?limit={}&page=1&tags={}",
+                    ITER_CHUNK_SIZE, tags
+                )),
+            )
+            .with_body(include_str!("mocked/id_8595_535_2105_1470.json"))
+            .create()
+        };
+
+        let m = fetch("fluffy");
+        let posts = client.cached_post_search("fluffy", 2).await.unwrap();
+        assert_eq!(posts.len(), 2);
+        drop(m);
+
+        // served from the cache, the server would answer with an error now
+        assert_eq!(client.cached_post_search("fluffy", 2).await, Ok(posts));
+        assert!(client.cached_post_search("fluffy", 3).await.is_err());
+
+        // evicts the results for `fluffy`, the cache only holds one search
+        let m = fetch("canine");
+        assert!(client.cached_post_search("canine", 2).await.is_ok());
+        drop(m);
+
+        assert!(client.cached_post_search("fluffy", 2).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn cached_search_per_user() {
+        let client = Client::builder(&mockito::server_url(), b"rs621/unit_test")
+            .search_cache(10, std::time::Duration::from_secs(60))
+            .build()
+            .unwrap();
+
+        let mut foo = client.clone();
+        foo.login(("foo", "bar"));
+
+        let mut baz = client.clone();
+        baz.login(("baz", "qux"));
+
+        let m = mock(
+            "GET",
+            Matcher::Exact(format!(
+                "/posts.json?limit={}&page=1&tags=fluffy&login=foo&api_key=bar",
+                ITER_CHUNK_SIZE
+            )),
+        )
+        .with_body(include_str!("mocked/id_8595_535_2105_1470.json"))
+        .create();
+
+        let posts = foo.cached_post_search("fluffy", 2).await.unwrap();
+        drop(m);
+
+        // the results of `foo` aren't served to other users, nor to anonymous clients
+        assert!(baz.cached_post_search("fluffy", 2).await.is_err());
+        assert!(client.cached_post_search("fluffy", 2).await.is_err());
+        assert_eq!(foo.cached_post_search("fluffy", 2).await, Ok(posts));
+    }
+
+    #[tokio::test]
+    async fn cached_search_expires() {
+        let client = Client::builder(&mockito::server_url(), b"rs621/unit_test")
+            .search_cache(10, std::time::Duration::ZERO)
+            .build()
+            .unwrap();
+
+        let m = mock(
+            "GET",
+            Matcher::Exact(format!(
+                "/posts.json?limit={}&page=1&tags=fluffy",
+                ITER_CHUNK_SIZE
+            )),
+        )
+        .with_body(include_str!("mocked/id_8595_535_2105_1470.json"))
+        .create();
+
+        assert!(client.cached_post_search("fluffy", 2).await.is_ok());
+        drop(m);
+
+        assert!(client.cached_post_search("fluffy", 2).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn random_posts() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let posts: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/id_8595_535_2105_1470.json")).unwrap();
+        let post = |i: usize| posts["posts"][i].clone();
+
+        let _m = [
+            // the first page has a post twice, so another one is needed
+            mock("GET", "/posts.json?limit=3&tags=fluffy%20order%3Arandom")
+                .with_body(serde_json::json!({ "posts": [post(0), post(1), post(0)] }).to_string())
+                .create(),
+            mock("GET", "/posts.json?limit=1&tags=fluffy%20order%3Arandom")
+                .with_body(serde_json::json!({ "posts": [post(2)] }).to_string())
+                .create(),
+        ];
+
+        let ids: Vec<u64> = client
+            .random_posts("fluffy order:score", 3)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|post| post.id)
+            .collect();
+
+        assert_eq!(ids, vec![8595, 2105, 1470]);
+    }
+
+    #[tokio::test]
+    async fn random_posts_not_enough() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock("GET", "/posts.json?limit=10&tags=fluffy%20order%3Arandom")
+            .with_body(include_str!("mocked/id_8595_535_2105_1470.json"))
+            .create();
+
+        assert_eq!(
+            client
+                .random_posts("fluffy", 10)
+                .await
+                .map(|posts| posts.len()),
+            Ok(4)
+        );
+    }
+
+    #[tokio::test]
+    async fn search_by_id_desc_past_page_limit() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let query = Query::from("fluffy").order(PostOrder::Id);
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact(format!(
+                    "/posts.json?limit={}&page=750&tags={}",
+                    ITER_CHUNK_SIZE, query.url_encoded_tags
+                )),
+            )
+            .with_body(include_str!("mocked/id_8595_535_2105_1470.json"))
+            .create(),
+            // page 751 is out of reach, the next posts are those older than the last one
+            mock(
+                "GET",
+                Matcher::Exact(format!(
+                    "/posts.json?limit={}&page=b535&tags={}",
+                    ITER_CHUNK_SIZE, query.url_encoded_tags
+                )),
             )
-            .await?;
-
-        serde_json::from_value(response).map_err(|e| Error::Serial(format!("{}", e)))
-    }
-}
+            .with_body(r#"{"posts":[]}"#)
+            .create(),
+        ];
 
-fn is_false(b: &bool) -> bool {
-    !b
-}
+        let ids: Vec<u64> = client
+            .post_search_from_page(query, SearchPage::Page(750))
+            .map_ok(|post| post.id)
+            .try_collect()
+            .await
+            .unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use mockito::{mock, Matcher};
+        assert_eq!(ids, vec![8595, 2105, 1470, 535]);
+    }
 
     #[tokio::test]
-    async fn post_vote_up_set() {
-        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
-        client.login("foo".into(), "bar".into());
+    async fn get_posts_into_remaining() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
 
-        let body = r#"{"score":41,"up":44,"down":-3,"our_score":1}"#;
+        // the first batch has 96 IDs the server doesn't know about
+        let mut ids = vec![8595, 535, 2105, 1470];
+        ids.extend(2..=99);
 
         let _m = mock(
-            "POST",
-            Matcher::Exact("/posts/1234/votes.json?login=foo&api_key=bar".into()),
+            "GET",
+            Matcher::Exact(format!(
+                "/posts.json?tags=id%3A{}",
+                ids[..100].iter().join(",")
+            )),
         )
-        .match_body("score=1&no_unvote=true")
-        .with_body(body)
+        .with_body(include_str!("mocked/id_8595_535_2105_1470.json"))
         .create();
 
-        let expected = serde_json::from_str::<VoteScore>(body).unwrap();
+        let mut stream = client.get_posts(ids);
 
-        assert_eq!(
-            client
-                .post_vote(1234, VoteMethod::Set, VoteDir::Up)
-                .await
-                .unwrap(),
-            expected
-        );
+        assert_eq!(stream.next().await.map(|p| p.map(|p| p.id)), Some(Ok(8595)));
+        assert_eq!(stream.next().await.map(|p| p.map(|p| p.id)), Some(Ok(2105)));
+
+        assert_eq!(stream.into_remaining(), vec![1470, 535, 98, 99]);
     }
 
     #[tokio::test]
-    async fn post_favorite() {
-        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
-        client.login("foo".into(), "bar".into());
+    async fn get_posts_into_remaining_after_invalid_post() {
+        let client = Client::builder(&mockito::server_url(), b"rs621/unit_test")
+            .validate_responses(true)
+            .build()
+            .unwrap();
 
-        let _m = mock(
-            "POST",
-            Matcher::Exact("/favorites.json?login=foo&api_key=bar".into()),
-        )
-        .match_body("post_id=3758515")
-        .with_body(include_str!("mocked/favorite.json"))
-        .create();
+        let mut invalid = post_with_parent(2001, None)["post"].take();
+        invalid["score"]["total"] = 1000.into();
+        let mut valid = post_with_parent(2000, None)["post"].take();
+        valid["score"]["total"] = 82.into();
 
-        let expected =
-            serde_json::from_str::<serde_json::Value>(include_str!("mocked/favorite.json"))
-                .unwrap()
-                .as_object()
-                .unwrap()
-                .get("post")
-                .cloned()
-                .unwrap();
+        let _m = mock("GET", "/posts.json?tags=id%3A2000,2001")
+            .with_body(serde_json::json!({ "posts": [invalid, valid] }).to_string())
+            .create();
 
-        assert_eq!(
-            client.post_favorite(3758515).await.unwrap(),
-            serde_json::from_value(expected).unwrap(),
-        );
+        let mut stream = client.get_posts(vec![2000, 2001]);
+
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(Error::Invalid { post_id: 2001, .. }))
+        ));
+        assert_eq!(stream.into_remaining(), vec![2000]);
     }
 
     #[tokio::test]
-    async fn post_unfavorite() {
-        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
-        client.login("foo".into(), "bar".into());
+    async fn get_posts_into_remaining_after_error() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
 
-        let _m = mock(
-            "POST",
-            Matcher::Exact("/favorites/3758515.json?login=foo&api_key=bar".into()),
-        )
-        .match_body("_method=delete")
-        .create();
+        let _m = mock("GET", "/posts.json?tags=id%3A1,2")
+            .with_status(500)
+            .create();
 
-        client.post_unfavorite(3758515).await.unwrap();
+        let mut stream = client.get_posts(vec![1, 2]);
+
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(Error::Http { code: 500, .. }))
+        ));
+        assert_eq!(stream.into_remaining(), vec![1, 2]);
     }
 
     #[tokio::test]
@@ -870,18 +3401,282 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn search_before_id() {
+    async fn search_collect_partial() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let query = Query::from(&["fluffy", "rating:s", "order:score"][..]);
+        const PAGE: &str = include_str!("mocked/320_page-1_fluffy_rating-s_order-score.json");
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact(format!(
+                    "/posts.json?limit={}&page=1&tags={}",
+                    ITER_CHUNK_SIZE, query.url_encoded_tags
+                )),
+            )
+            .with_body(PAGE)
+            .create(),
+            mock(
+                "GET",
+                Matcher::Exact(format!(
+                    "/posts.json?limit={}&page=2&tags={}",
+                    ITER_CHUNK_SIZE, query.url_encoded_tags
+                )),
+            )
+            .with_status(500)
+            .create(),
+        ];
+
+        let (posts, error) = client.post_search_collect_partial(query, 400).await;
+
+        assert_eq!(
+            posts,
+            serde_json::from_str::<PostListApiResponse>(PAGE)
+                .unwrap()
+                .posts
+        );
+        assert!(matches!(error, Some(Error::Http { code: 500, .. })));
+    }
+
+    #[tokio::test]
+    async fn search_before_id() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let query = Query::from(&["fluffy", "rating:s"][..]);
+        let response_json = include_str!("mocked/320_fluffy_rating-s_before-2269211.json");
+        let response: PostListApiResponse = serde_json::from_str(response_json).unwrap();
+        let expected: Vec<_> = response.posts.into_iter().take(80).map(|x| Ok(x)).collect();
+
+        let _m = mock(
+            "GET",
+            Matcher::Exact(format!(
+                "/posts.json?limit={}&page=b2269211&tags={}",
+                ITER_CHUNK_SIZE, query.url_encoded_tags
+            )),
+        )
+        .with_body(response_json)
+        .create();
+
+        assert_eq!(
+            client
+                .post_search_from_page(query, SearchPage::BeforePost(2269211))
+                .take(80)
+                .collect::<Vec<_>>()
+                .await,
+            expected
+        );
+    }
+
+    #[tokio::test]
+    async fn search_malformed_post() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let query = Query::from(&["fluffy", "rating:s"][..]);
+        let mut response: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/320_fluffy_rating-s.json")).unwrap();
+        response["posts"][1]["id"] = "not an id".into();
+
+        let _m = mock(
+            "GET",
+            Matcher::Exact(format!(
+                "/posts.json?limit={}&page=1&tags={}",
+                ITER_CHUNK_SIZE, query.url_encoded_tags
+            )),
+        )
+        .with_body(response.to_string())
+        .create();
+
+        // posts are deserialized one at a time, so only the malformed one is an error
+        let results = client.post_search(query).take(3).collect::<Vec<_>>().await;
+
+        assert!(matches!(results[0], Ok(ref post) if post.id == response["posts"][0]["id"]));
+        assert!(matches!(results[1], Err(Error::Serial(_))));
+        assert!(matches!(results[2], Ok(ref post) if post.id == response["posts"][2]["id"]));
+    }
+
+    #[tokio::test]
+    async fn search_with_params() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = [
+            mock(
+                "GET",
+                "/posts.json?limit=320&page=1&tags=fluffy&only=id%2Cmd5&foo=a%26b",
+            )
+            .with_body(r#"{"posts":[{"id":3,"md5":"c"},{"id":2,"md5":"b"}]}"#)
+            .create(),
+            mock(
+                "GET",
+                "/posts.json?limit=320&page=b2&tags=fluffy&only=id%2Cmd5&foo=a%26b",
+            )
+            .with_body(r#"{"posts":[]}"#)
+            .create(),
+        ];
+
+        assert_eq!(
+            client
+                .post_search_with_params("fluffy", &[("only", "id,md5"), ("foo", "a&b")])
+                .collect::<Vec<_>>()
+                .await,
+            vec![
+                Ok(serde_json::json!({"id": 3, "md5": "c"})),
+                Ok(serde_json::json!({"id": 2, "md5": "b"})),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn search_ids() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = [
+            mock("GET", "/posts.json?limit=320&page=1&tags=fluffy&only=id")
+                .with_body(r#"{"posts":[{"id":3},{"id":2}]}"#)
+                .create(),
+            mock("GET", "/posts.json?limit=320&page=b2&tags=fluffy&only=id")
+                .with_body(r#"{"posts":[]}"#)
+                .create(),
+        ];
+
+        assert_eq!(
+            client.post_search_ids("fluffy").collect::<Vec<_>>().await,
+            vec![Ok(3), Ok(2)]
+        );
+    }
+
+    #[tokio::test]
+    async fn search_md5s() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = [
+            mock(
+                "GET",
+                "/posts.json?limit=320&page=1&tags=fluffy&only=id%2Cfile",
+            )
+            .with_body(r#"{"posts":[{"id":3,"file":{"md5":"c","url":null}},{"id":2,"file":{}}]}"#)
+            .create(),
+            mock(
+                "GET",
+                "/posts.json?limit=320&page=b2&tags=fluffy&only=id%2Cfile",
+            )
+            .with_body(r#"{"posts":[]}"#)
+            .create(),
+        ];
+
+        let md5s = client.post_search_md5s("fluffy").collect::<Vec<_>>().await;
+
+        assert_eq!(md5s.len(), 2);
+        assert_eq!(md5s[0], Ok(String::from("c")));
+        assert!(matches!(md5s[1], Err(Error::Serial(_))));
+    }
+
+    #[tokio::test]
+    async fn search_raw() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let query = Query::from(&["fluffy", "rating:s"][..]);
+        let response_json = include_str!("mocked/320_fluffy_rating-s.json");
+        let response: serde_json::Value = serde_json::from_str(response_json).unwrap();
+        let expected: Vec<_> = response["posts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .take(80)
+            .cloned()
+            .map(Ok)
+            .collect();
+
+        let _m = mock(
+            "GET",
+            Matcher::Exact(format!(
+                "/posts.json?limit={}&page=1&tags={}",
+                ITER_CHUNK_SIZE, query.url_encoded_tags
+            )),
+        )
+        .with_body(response_json)
+        .create();
+
+        assert_eq!(
+            client
+                .post_search_raw(query)
+                .take(80)
+                .collect::<Vec<_>>()
+                .await,
+            expected
+        );
+    }
+
+    #[tokio::test]
+    async fn search_difference() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let include = Query::from(&["fluffy", "rating:s"][..]);
+        let exclude = Query::from("pool:1");
+        let response_json = include_str!("mocked/320_fluffy_rating-s.json");
+        let response: PostListApiResponse = serde_json::from_str(response_json).unwrap();
+
+        // the first 3 posts of the include search are excluded
+        let raw: serde_json::Value = serde_json::from_str(response_json).unwrap();
+        let excluded: Vec<_> = raw["posts"].as_array().unwrap()[..3]
+            .iter()
+            .map(|post| serde_json::json!({ "id": post["id"] }))
+            .collect();
+        let excluded = serde_json::json!({ "posts": excluded });
+        let last_excluded = response.posts[2].id;
+        let expected: Vec<_> = response.posts[3..13].to_vec();
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact(format!(
+                    "/posts.json?limit={}&page=1&tags={}",
+                    ITER_CHUNK_SIZE, include.url_encoded_tags
+                )),
+            )
+            .with_body(response_json)
+            .create(),
+            mock(
+                "GET",
+                Matcher::Exact(format!(
+                    "/posts.json?limit={}&page=1&tags={}&only=id",
+                    ITER_CHUNK_SIZE, exclude.url_encoded_tags
+                )),
+            )
+            .with_body(excluded.to_string())
+            .create(),
+            mock(
+                "GET",
+                Matcher::Exact(format!(
+                    "/posts.json?limit={}&page=b{}&tags={}&only=id",
+                    ITER_CHUNK_SIZE, last_excluded, exclude.url_encoded_tags
+                )),
+            )
+            .with_body(r#"{"posts":[]}"#)
+            .create(),
+        ];
+
+        assert_eq!(
+            client.search_difference(include, exclude, 10).await,
+            Ok(expected)
+        );
+    }
+
+    #[tokio::test]
+    async fn search_until() {
         let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
 
         let query = Query::from(&["fluffy", "rating:s"][..]);
-        let response_json = include_str!("mocked/320_fluffy_rating-s_before-2269211.json");
+        let response_json = include_str!("mocked/320_fluffy_rating-s.json");
         let response: PostListApiResponse = serde_json::from_str(response_json).unwrap();
-        let expected: Vec<_> = response.posts.into_iter().take(80).map(|x| Ok(x)).collect();
+        let stop_at_id = response.posts[50].id;
+        let expected: Vec<_> = response.posts.into_iter().take(50).map(Ok).collect();
 
+        // only the first page is mocked: the stream must stop before asking for the second
         let _m = mock(
             "GET",
             Matcher::Exact(format!(
-                "/posts.json?limit={}&page=b2269211&tags={}",
+                "/posts.json?limit={}&page=1&tags={}",
                 ITER_CHUNK_SIZE, query.url_encoded_tags
             )),
         )
@@ -890,8 +3685,7 @@ mod tests {
 
         assert_eq!(
             client
-                .post_search_from_page(query, SearchPage::BeforePost(2269211))
-                .take(80)
+                .post_search_until(query, stop_at_id)
                 .collect::<Vec<_>>()
                 .await,
             expected
@@ -1000,6 +3794,571 @@ mod tests {
         );
     }
 
+    #[test]
+    fn blacklist_wildcards() {
+        let post = serde_json::from_str::<PostShowApiResponse>(include_str!("mocked/id_8595.json"))
+            .unwrap()
+            .post;
+
+        assert!(Blacklist::new(&["domestic_cat"]).is_blacklisted(&post));
+        assert!(Blacklist::new(&["domestic_*"]).is_blacklisted(&post));
+        assert!(Blacklist::new(&["*_pencil_*"]).is_blacklisted(&post));
+        assert!(Blacklist::new(&["*fur"]).is_blacklisted(&post));
+        assert!(!Blacklist::new(&["domestic"]).is_blacklisted(&post));
+        assert!(!Blacklist::new(&["*_horse"]).is_blacklisted(&post));
+        assert!(!Blacklist::new::<&str>(&[]).is_blacklisted(&post));
+    }
+
+    #[test]
+    fn blacklist_exceptions() {
+        let post = serde_json::from_str::<PostShowApiResponse>(include_str!("mocked/id_8595.json"))
+            .unwrap()
+            .post;
+
+        assert!(!Blacklist::new(&["mammal", "-jessica_willard"]).is_blacklisted(&post));
+        assert!(!Blacklist::new(&["mammal", "-2005"]).is_blacklisted(&post));
+        assert!(Blacklist::new(&["mammal", "-2006"]).is_blacklisted(&post));
+
+        // exceptions alone never hide anything
+        assert!(!Blacklist::new(&["-mammal"]).is_blacklisted(&post));
+    }
+
+    #[tokio::test]
+    async fn get_posts_filter_blacklist() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let response_json = include_str!("mocked/id_8595_535_2105_1470.json");
+        let response: PostListApiResponse = serde_json::from_str(response_json).unwrap();
+
+        // #8595 is the only canine that isn't a fox
+        let expected: Vec<_> = response
+            .posts
+            .into_iter()
+            .filter(|p| p.id != 8595)
+            .map(Ok)
+            .collect();
+
+        let _m = mock("GET", "/posts.json?tags=id%3A8595,535,2105,1470")
+            .with_body(response_json)
+            .create();
+
+        assert_eq!(
+            client
+                .get_posts(&[8595, 535, 2105, 1470])
+                .filter_blacklist(&["can*", "-fox"])
+                .collect::<Vec<_>>()
+                .await,
+            expected,
+        );
+    }
+
+    #[test]
+    fn post_file_name() {
+        let mut post =
+            serde_json::from_str::<PostShowApiResponse>(include_str!("mocked/id_8595.json"))
+                .unwrap()
+                .post;
+
+        assert_eq!(
+            post.file_name(),
+            Some(String::from("e9fbd2f2d0703a9775f245d55b9a0f9f.jpg"))
+        );
+
+        post.file.url = None;
+        assert_eq!(post.file_name(), None);
+    }
+
+    #[test]
+    fn query_voted() {
+        assert_eq!(
+            Query::new()
+                .voted_up_by(None)
+                .voted_down_by(Some("some user"))
+                .voted_by(Some("foo"))
+                .tags(),
+            vec!["votedup:me", "voteddown:some_user", "voted:foo"]
+        );
+    }
+
+    #[test]
+    fn post_source_urls() {
+        let mut post =
+            serde_json::from_str::<PostShowApiResponse>(include_str!("mocked/id_8595.json"))
+                .unwrap()
+                .post;
+
+        post.sources
+            .push(String::from("Source: the artist's gallery"));
+
+        // the artist's name and the free text aren't URLs
+        assert_eq!(
+            post.source_urls(),
+            vec![
+                Url::parse("https://us-p.vclart.net/vcl/Artists/J-Willard/jw-babysteps.jpg")
+                    .unwrap(),
+                Url::parse("https://www.furaffinity.net/view/185399/").unwrap(),
+            ]
+        );
+
+        assert!(post.has_source_from("furaffinity.net"));
+        assert!(post.has_source_from("www.furaffinity.net"));
+        assert!(post.has_source_from("VCLART.net"));
+        assert!(!post.has_source_from("affinity.net"));
+        assert!(!post.has_source_from("twitter.com"));
+    }
+
+    #[test]
+    fn post_artist_sources() {
+        let mut post =
+            serde_json::from_str::<PostShowApiResponse>(include_str!("mocked/id_8595.json"))
+                .unwrap()
+                .post;
+
+        assert_eq!(
+            post.primary_source(),
+            Some(r#"Jessica Willard, "jw-babysteps.jpg""#)
+        );
+        assert_eq!(
+            post.artist_sources(),
+            vec!["https://www.furaffinity.net/view/185399/"]
+        );
+
+        post.sources = vec![
+            String::from("  "),
+            String::from("https://pbs.twimg.com/media/foo.jpg"),
+            String::from("https://notdeviantart.com/art/bar"),
+            String::from("https://www.deviantart.com/baz/art/bar"),
+        ];
+
+        assert_eq!(
+            post.primary_source(),
+            Some("https://pbs.twimg.com/media/foo.jpg")
+        );
+        assert_eq!(
+            post.artist_sources(),
+            vec![
+                "https://pbs.twimg.com/media/foo.jpg",
+                "https://www.deviantart.com/baz/art/bar",
+            ]
+        );
+
+        post.sources.clear();
+        assert_eq!(post.primary_source(), None);
+    }
+
+    #[test]
+    fn post_embed() {
+        let client = Client::new("https://e926.net", b"rs621/unit_test").unwrap();
+        let mut post =
+            serde_json::from_str::<PostShowApiResponse>(include_str!("mocked/id_8595.json"))
+                .unwrap()
+                .post;
+
+        let embed = post.embed(&client);
+        assert_eq!(embed.title, "Post #8595 by jessica willard");
+        assert_eq!(embed.description_snippet, "");
+        assert_eq!(embed.page_url.as_str(), "https://e926.net/posts/8595");
+        assert_eq!(embed.thumbnail_url, post.preview.url);
+        assert_eq!((embed.width, embed.height), (800, 616));
+        assert_eq!(embed.rating, post.rating);
+
+        post.tags.character = vec![String::from("krystal"), String::from("fox_mccloud")];
+        post.tags.artist = vec![String::from("conditional_dnp"), String::from("foo_bar")];
+        post.description = format!("[b]Hello[/b]\n\n{}", "a".repeat(300));
+
+        let embed = post.embed(&client);
+        assert_eq!(embed.title, "krystal by foo bar");
+        assert_eq!(
+            embed.description_snippet,
+            format!("Hello {}…", "a".repeat(194))
+        );
+
+        post.tags.character.clear();
+        post.tags.artist = vec![String::from("unknown_artist")];
+        assert_eq!(post.embed(&client).title, "Post #8595");
+    }
+
+    #[test]
+    fn post_serialize_round_trip() {
+        let post = serde_json::from_str::<PostShowApiResponse>(include_str!("mocked/id_8595.json"))
+            .unwrap()
+            .post;
+
+        let json = serde_json::to_string(&post).unwrap();
+        assert_eq!(serde_json::from_str::<Post>(&json).unwrap(), post);
+    }
+
+    #[test]
+    fn post_dnp() {
+        let mut post =
+            serde_json::from_str::<PostShowApiResponse>(include_str!("mocked/id_8595.json"))
+                .unwrap()
+                .post;
+
+        assert!(!post.is_conditional_dnp());
+        assert!(!post.is_avoid_posting());
+
+        post.tags.artist.push(String::from("conditional_dnp"));
+        assert!(post.is_conditional_dnp());
+        assert!(!post.is_avoid_posting());
+
+        // only the artist, meta and lore tags are checked
+        post.tags.general.push(String::from("avoid_posting"));
+        assert!(!post.is_avoid_posting());
+
+        post.tags.meta.push(String::from("avoid_posting"));
+        assert!(post.is_avoid_posting());
+    }
+
+    #[test]
+    fn post_has_sound() {
+        let mut post =
+            serde_json::from_str::<PostShowApiResponse>(include_str!("mocked/id_8595.json"))
+                .unwrap()
+                .post;
+
+        assert!(!post.has_sound());
+
+        post.tags.meta.push(String::from("sound"));
+        assert!(post.has_sound());
+    }
+
+    #[test]
+    fn post_age() {
+        let mut post =
+            serde_json::from_str::<PostShowApiResponse>(include_str!("mocked/id_8595.json"))
+                .unwrap()
+                .post;
+
+        post.created_at = Utc::now() - chrono::Duration::days(3);
+        post.updated_at = None;
+
+        assert!(post.age() >= chrono::Duration::days(3));
+        assert!(post.age() < chrono::Duration::days(3) + chrono::Duration::minutes(1));
+        assert_eq!(post.updated_age(), None);
+
+        post.updated_at = Some(Utc::now() - chrono::Duration::hours(1));
+        assert!(matches!(post.updated_age(), Some(age) if age >= chrono::Duration::hours(1)));
+    }
+
+    #[test]
+    fn post_locked_tags_by_category() {
+        let mut post =
+            serde_json::from_str::<PostShowApiResponse>(include_str!("mocked/id_8595.json"))
+                .unwrap()
+                .post;
+
+        post.locked_tags = vec!["jessica_willard".into(), "-young".into(), "fur".into()];
+
+        let locked = post.locked_tags_by_category();
+
+        assert_eq!(locked.artist, vec!["jessica_willard"]);
+        assert_eq!(locked.general, vec!["fur"]);
+        assert_eq!(locked.iter().count(), 2);
+    }
+
+    #[test]
+    fn query_tags() {
+        let query = Query::from(&["fluffy", "rating:s  order:score"][..]);
+
+        assert_eq!(query.tags(), vec!["fluffy", "rating:s", "order:score"]);
+        assert!(query.is_ordered());
+        assert_eq!(
+            query.url_encoded_tags,
+            "fluffy%20rating%3As%20order%3Ascore"
+        );
+
+        let query = Query::from(&["fluffy"][..]);
+
+        assert_eq!(query.tags(), vec!["fluffy"]);
+        assert!(!query.is_ordered());
+    }
+
+    #[test]
+    fn query_from_str_and_vec() {
+        let expected = Query::from(&["fluffy", "rating:s", "order:score"][..]);
+
+        assert_eq!(Query::from("fluffy rating:s  order:score"), expected);
+        assert_eq!(Query::from(" fluffy\trating:s order:score\n"), expected);
+        assert_eq!(
+            Query::from(vec!["fluffy", "rating:s", "order:score"]),
+            expected
+        );
+        assert_eq!(
+            Query::from(vec![
+                String::from("fluffy rating:s"),
+                String::from("order:score")
+            ]),
+            expected
+        );
+    }
+
+    #[test]
+    fn query_approver() {
+        assert_eq!(
+            Query::new().approved_by(" some  janitor ").tags(),
+            vec!["approver:some_janitor"]
+        );
+        assert_eq!(Query::new().unapproved().tags(), vec!["approver:none"]);
+    }
+
+    #[test]
+    fn query_uploader() {
+        assert_eq!(
+            Query::new().uploaded_by("some artist").tags(),
+            vec!["user:some_artist"]
+        );
+        assert_eq!(
+            Query::new().uploaded_by_id(1234).tags(),
+            vec!["user_id:1234"]
+        );
+    }
+
+    #[test]
+    fn query_relationships() {
+        assert_eq!(Query::new().parent(8595).tags(), vec!["parent:8595"]);
+        assert_eq!(Query::new().parent_none().tags(), vec!["parent:none"]);
+        assert_eq!(
+            Query::new().has_children(true).tags(),
+            vec!["isparent:true"]
+        );
+        assert_eq!(
+            Query::new().has_children(false).tags(),
+            vec!["isparent:false"]
+        );
+    }
+
+    #[test]
+    fn query_sound() {
+        assert_eq!(Query::new().has_sound(true).tags(), vec!["sound"]);
+        assert_eq!(Query::new().has_sound(false).tags(), vec!["-sound"]);
+    }
+
+    #[test]
+    fn query_pools() {
+        assert_eq!(Query::new().in_pool(true).tags(), vec!["inpool:true"]);
+        assert_eq!(Query::new().in_pool(false).tags(), vec!["inpool:false"]);
+        assert_eq!(Query::new().no_pool().tags(), vec!["pool:none"]);
+    }
+
+    #[test]
+    fn query_ranges() {
+        assert_eq!(range_meta_value(..), None);
+        assert_eq!(range_meta_value(10..), Some(String::from(">=10")));
+        assert_eq!(range_meta_value(..=10), Some(String::from("<=10")));
+        assert_eq!(range_meta_value(..10), Some(String::from("<=9")));
+        assert_eq!(range_meta_value(..0), Some(String::from("<0")));
+        assert_eq!(range_meta_value(5..10), Some(String::from("5..9")));
+        assert_eq!(range_meta_value(5..=10), Some(String::from("5..10")));
+        assert_eq!(range_meta_value(5..=5), Some(String::from("5")));
+
+        assert_eq!(
+            Query::new()
+                .fav_count_range(100..)
+                .comment_count_range(1..=10)
+                .fav_count_range(..)
+                .tags(),
+            vec!["favcount:>=100", "comment_count:1..10"]
+        );
+    }
+
+    #[test]
+    fn query_order() {
+        let expected = [
+            (PostOrder::Id, "order:id_desc", "order:id_asc"),
+            (PostOrder::Score, "order:score", "order:score_asc"),
+            (PostOrder::Favorites, "order:favcount", "order:favcount_asc"),
+            (
+                PostOrder::CreatedAt,
+                "order:created_at",
+                "order:created_at_asc",
+            ),
+            (PostOrder::UpdatedAt, "order:updated", "order:updated_asc"),
+            (
+                PostOrder::Comments,
+                "order:comment_count",
+                "order:comment_count_asc",
+            ),
+            (PostOrder::Mpixels, "order:mpixels", "order:mpixels_asc"),
+            (PostOrder::FileSize, "order:filesize", "order:filesize_asc"),
+            (PostOrder::TagCount, "order:tagcount", "order:tagcount_asc"),
+            (PostOrder::Random, "order:random", "order:random"),
+        ];
+
+        for (order, desc, asc) in expected.iter() {
+            let query = Query::new().order(*order);
+            assert_eq!(query.tags(), vec![*desc]);
+            assert!(query.is_ordered());
+
+            let query = Query::new().order_asc(*order);
+            assert_eq!(query.tags(), vec![*asc]);
+            assert!(query.is_ordered());
+        }
+    }
+
+    #[test]
+    fn query_order_replaces_previous_order() {
+        let query = Query::from(&["order:score", "fluffy"][..]).order(PostOrder::Random);
+
+        assert_eq!(query.tags(), vec!["fluffy", "order:random"]);
+        assert_eq!(query, Query::from(&["fluffy", "order:random"][..]));
+    }
+
+    #[tokio::test]
+    async fn search_too_many_tags() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let tags: Vec<_> = (0..=QUERY_TAG_LIMIT)
+            .map(|i| format!("-tag{}", i))
+            .collect();
+        let query = Query::from(&tags[..]);
+
+        assert_eq!(
+            client.post_search(query).collect::<Vec<_>>().await,
+            vec![Err(Error::TooManyTags {
+                count: QUERY_TAG_LIMIT + 1,
+                limit: QUERY_TAG_LIMIT,
+            })]
+        );
+    }
+
+    #[cfg(feature = "timeout")]
+    #[tokio::test]
+    async fn search_page_timeout() {
+        // accepts connections but never answers
+        let server = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+
+        let client = Client::builder(
+            &format!("http://{}", server.local_addr().unwrap()),
+            b"rs621/unit_test",
+        )
+        .page_timeout(std::time::Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+        let mut stream = client.post_search("fluffy");
+
+        // the stream doesn't end after a timeout, it tries the page again
+        assert_eq!(stream.next().await, Some(Err(Error::Timeout)));
+        assert_eq!(stream.next().await, Some(Err(Error::Timeout)));
+    }
+
+    #[test]
+    fn query_encodes_special_characters() {
+        let cases = [
+            ("greater_than_(symbol)", "greater_than_%28symbol%29"),
+            ("rock_&_roll", "rock_%26_roll"),
+            ("c++", "c%2B%2B"),
+            ("#1", "%231"),
+            ("100%", "100%25"),
+            (
+                "source:https://a.b/?c=d",
+                "source%3Ahttps%3A%2F%2Fa.b%2F%3Fc%3Dd",
+            ),
+        ];
+
+        for (tag, encoded) in cases.iter() {
+            assert_eq!(Query::from(&[*tag][..]).url_encoded_tags, *encoded);
+        }
+
+        assert_eq!(
+            Query::from(&["c++", "rock_&_roll"][..]).url_encoded_tags,
+            "c%2B%2B%20rock_%26_roll"
+        );
+    }
+
+    #[tokio::test]
+    async fn search_special_characters() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock(
+            "GET",
+            Matcher::Exact(format!(
+                "/posts.json?limit={}&page=1&tags=c%2B%2B%20rock_%26_roll%20%231",
+                ITER_CHUNK_SIZE
+            )),
+        )
+        .with_body(r#"{"posts":[]}"#)
+        .create();
+
+        assert_eq!(
+            client
+                .post_search(&["c++", "rock_&_roll", "#1"][..])
+                .collect::<Vec<_>>()
+                .await,
+            vec![]
+        );
+    }
+
+    #[tokio::test]
+    async fn search_thumbnails() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let query = Query::from(&["fluffy", "rating:s", "order:score"][..]);
+        let response_json = include_str!("mocked/320_page-1_fluffy_rating-s_order-score.json");
+        let response: PostListApiResponse = serde_json::from_str(response_json).unwrap();
+
+        let _m = mock(
+            "GET",
+            Matcher::Exact(format!(
+                "/posts.json?limit={}&page=1&tags={}",
+                ITER_CHUNK_SIZE, query.url_encoded_tags
+            )),
+        )
+        .with_body(response_json)
+        .create();
+
+        assert_eq!(
+            client
+                .post_search_thumbnails(query)
+                .take(20)
+                .collect::<Vec<_>>()
+                .await,
+            response
+                .posts
+                .iter()
+                .take(20)
+                .map(|post| Ok(post.thumbnail()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn streams_are_send() {
+        // the streams borrow the client, which has to outlive the spawned tasks
+        let client: &'static Client = Box::leak(Box::new(
+            Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap(),
+        ));
+
+        let query = Query::from(&["fluffy", "rating:s", "order:score"][..]);
+        let response_json = include_str!("mocked/id_8595_535_2105_1470.json");
+        let response: PostListApiResponse = serde_json::from_str(response_json).unwrap();
+
+        let _m = [
+            mock("GET", "/posts.json?tags=id%3A8595,535,2105,1470")
+                .with_body(response_json)
+                .create(),
+            mock(
+                "GET",
+                Matcher::Exact(format!(
+                    "/posts.json?limit={}&page=1&tags={}",
+                    ITER_CHUNK_SIZE, query.url_encoded_tags
+                )),
+            )
+            .with_body(response_json)
+            .create(),
+        ];
+
+        let mut search = client.post_search(query);
+        let search = tokio::spawn(async move { search.next().await });
+
+        let mut posts = client.get_posts(&[8595, 535, 2105, 1470]);
+        let posts = tokio::spawn(async move { posts.next().await });
+
+        assert_eq!(search.await.unwrap(), Some(Ok(response.posts[0].clone())));
+        assert_eq!(posts.await.unwrap(), Some(Ok(response.posts[0].clone())));
+    }
+
     #[tokio::test]
     async fn get_posts_by_id() {
         let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
@@ -0,0 +1,126 @@
+use {
+    super::{
+        client::{query_param, Client, PageStream},
+        error::Result as Rs621Result,
+        post::PostRating,
+    },
+    chrono::{offset::Utc, DateTime},
+    futures::prelude::*,
+    serde::Deserialize,
+};
+
+/// Structure representing a version of a post, i.e. one edit in its history.
+#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[non_exhaustive]
+pub struct PostVersion {
+    pub id: u64,
+    pub post_id: u64,
+    /// Which edit of the post this is, starting at 1 for its upload.
+    pub version: u64,
+    pub updater_id: Option<u64>,
+    pub updater_name: Option<String>,
+    #[serde(deserialize_with = "crate::datetime::deserialize")]
+    pub updated_at: DateTime<Utc>,
+    /// The edit summary, if the updater gave one.
+    pub reason: Option<String>,
+    /// The tags of the post after this edit, separated by spaces.
+    pub tags: String,
+    pub added_tags: Vec<String>,
+    pub removed_tags: Vec<String>,
+    pub rating: PostRating,
+    pub rating_changed: bool,
+    pub parent_id: Option<u64>,
+    pub parent_changed: bool,
+    pub source: String,
+    pub source_changed: bool,
+    pub description: String,
+    pub description_changed: bool,
+}
+
+impl Client {
+    /// Returns a Stream over the edits made by the given user, newest first.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut versions = client.user_post_versions("username").take(20);
+    ///
+    /// while let Some(version) = versions.next().await {
+    ///     let version = version?;
+    ///     println!(
+    ///         "#{}: +{:?} -{:?}",
+    ///         version.post_id, version.added_tags, version.removed_tags
+    ///     );
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn user_post_versions(
+        &self,
+        username: &str,
+    ) -> impl Stream<Item = Rs621Result<PostVersion>> + '_ {
+        PageStream::new(
+            self,
+            format!(
+                "/post_versions.json?limit=320&{}",
+                query_param("search[updater_name]", username),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn user_post_versions() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact(
+                    "/post_versions.json?limit=320&search%5Bupdater_name%5D=foo&page=1".into(),
+                ),
+            )
+            .with_body(include_str!("mocked/post_versions_foo.json"))
+            .create(),
+            // have the next page be empty to end the stream
+            mock(
+                "GET",
+                Matcher::Exact(
+                    "/post_versions.json?limit=320&search%5Bupdater_name%5D=foo&page=2".into(),
+                ),
+            )
+            .with_body("[]")
+            .create(),
+        ];
+
+        let versions: Vec<PostVersion> = client
+            .user_post_versions("foo")
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            versions
+                .iter()
+                .map(|v| (v.post_id, v.version))
+                .collect::<Vec<_>>(),
+            vec![(4621377, 3), (4621290, 2)]
+        );
+        assert_eq!(versions[0].added_tags, vec!["fluffy", "solo"]);
+        assert!(versions[0].source_changed);
+        assert_eq!(versions[1].rating, PostRating::Questionable);
+        assert_eq!(
+            versions[1].reason.as_deref(),
+            Some("Parent is the better version")
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use crate::error::Error;
+
+use super::{client::Client, error::Result as Rs621Result};
+
+impl Client {
+    /// Adds posts to the post set identified by `set_id`. The logged in user must own or maintain
+    /// the set, otherwise this fails with [`Error::Unauthorized`].
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let mut client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// client.login(("username", "api_key"));
+    ///
+    /// client.add_to_set(1234, &[8595, 535]).await?;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [`Error::Unauthorized`]: ../error/enum.Error.html#variant.Unauthorized
+    pub async fn add_to_set(&self, set_id: u64, post_ids: &[u64]) -> Rs621Result<()> {
+        self.update_set_posts(&format!("/post_sets/{set_id}/add_posts.json"), post_ids)
+            .await
+    }
+
+    /// Removes posts from the post set identified by `set_id`. The logged in user must own or
+    /// maintain the set, otherwise this fails with [`Error::Unauthorized`].
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let mut client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    /// client.login(("username", "api_key"));
+    ///
+    /// client.remove_from_set(1234, &[8595, 535]).await?;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [`Error::Unauthorized`]: ../error/enum.Error.html#variant.Unauthorized
+    pub async fn remove_from_set(&self, set_id: u64, post_ids: &[u64]) -> Rs621Result<()> {
+        self.update_set_posts(&format!("/post_sets/{set_id}/remove_posts.json"), post_ids)
+            .await
+    }
+
+    async fn update_set_posts(&self, endpoint: &str, post_ids: &[u64]) -> Rs621Result<()> {
+        self.require_login()?;
+
+        // all the IDs go in a single request, as `post_ids[]=1&post_ids[]=2...`
+        let form: Vec<(&str, u64)> = post_ids.iter().map(|&id| ("post_ids[]", id)).collect();
+
+        match self.post_response(endpoint, &form).await {
+            Ok(_) => Ok(()),
+            Err(Error::Http { code: 403, .. }) => Err(Error::Unauthorized),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn add_to_set() {
+        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        client.login(("foo", "bar"));
+
+        let _m = mock(
+            "POST",
+            Matcher::Exact("/post_sets/42/add_posts.json?login=foo&api_key=bar".into()),
+        )
+        .match_body("post_ids%5B%5D=8595&post_ids%5B%5D=535")
+        .with_body("{}")
+        .create();
+
+        assert_eq!(client.add_to_set(42, &[8595, 535]).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn remove_from_set_forbidden() {
+        let mut client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+        client.login(("foo", "bar"));
+
+        let _m = mock(
+            "POST",
+            Matcher::Exact("/post_sets/43/remove_posts.json?login=foo&api_key=bar".into()),
+        )
+        .match_body("post_ids%5B%5D=8595")
+        .with_status(403)
+        .with_body(r#"{"success":false,"reason":"Access Denied"}"#)
+        .create();
+
+        assert_eq!(
+            client.remove_from_set(43, &[8595]).await,
+            Err(Error::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn set_writes_require_login() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        assert_eq!(client.add_to_set(42, &[1]).await, Err(Error::Unauthorized));
+        assert_eq!(
+            client.remove_from_set(42, &[1]).await,
+            Err(Error::Unauthorized)
+        );
+    }
+}
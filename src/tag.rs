@@ -0,0 +1,1410 @@
+use crate::error::Error;
+
+use {
+    super::{
+        client::{query_param, Client, PageStream},
+        error::Result as Rs621Result,
+        post::{Post, Query as PostQuery},
+    },
+    chrono::{offset::Utc, DateTime},
+    futures::prelude::*,
+    itertools::Itertools,
+    serde::{Deserialize, Deserializer},
+    std::{
+        collections::{HashMap, HashSet, VecDeque},
+        convert::TryFrom,
+        ops::RangeInclusive,
+    },
+};
+
+/// Maximum number of tag names looked up in a single request.
+const NAMES_CHUNK_SIZE: usize = 100;
+
+/// Maximum number of implication levels followed by [`Client::implication_tree`].
+const IMPLICATION_TREE_MAX_DEPTH: usize = 20;
+
+/// Category of a tag.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Deserialize)]
+#[serde(try_from = "u8")]
+pub enum Category {
+    General,
+    Artist,
+    Copyright,
+    Character,
+    Species,
+    Invalid,
+    Meta,
+    Lore,
+}
+
+impl TryFrom<u8> for Category {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Category::General),
+            1 => Ok(Category::Artist),
+            3 => Ok(Category::Copyright),
+            4 => Ok(Category::Character),
+            5 => Ok(Category::Species),
+            6 => Ok(Category::Invalid),
+            7 => Ok(Category::Meta),
+            8 => Ok(Category::Lore),
+            other => Err(format!("unknown tag category: {}", other)),
+        }
+    }
+}
+
+impl From<Category> for u8 {
+    fn from(category: Category) -> Self {
+        match category {
+            Category::General => 0,
+            Category::Artist => 1,
+            Category::Copyright => 3,
+            Category::Character => 4,
+            Category::Species => 5,
+            Category::Invalid => 6,
+            Category::Meta => 7,
+            Category::Lore => 8,
+        }
+    }
+}
+
+/// Deserializes `related_tags`, which is a space separated string, but may also come as an array
+/// (of strings, or of `[tag, score]` pairs). Arrays are flattened and joined with spaces.
+fn related_tags_from_json<'de, D>(de: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    fn flatten(value: serde_json::Value, words: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Array(values) => {
+                for value in values {
+                    flatten(value, words);
+                }
+            }
+            serde_json::Value::String(s) => words.push(s),
+            serde_json::Value::Null => (),
+            other => words.push(other.to_string()),
+        }
+    }
+
+    match serde_json::Value::deserialize(de)? {
+        serde_json::Value::String(s) => Ok(s),
+        value => {
+            let mut words = Vec::new();
+            flatten(value, &mut words);
+            Ok(words.join(" "))
+        }
+    }
+}
+
+/// Structure representing a tag.
+#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[non_exhaustive]
+pub struct Tag {
+    pub id: u64,
+    pub name: String,
+    pub post_count: u64,
+    /// Related tags and their scores, separated by spaces (`"tag1 300 tag2 216 ..."`).
+    #[serde(deserialize_with = "related_tags_from_json")]
+    pub related_tags: String,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_option")]
+    pub related_tags_updated_at: Option<DateTime<Utc>>,
+    pub category: Category,
+    pub is_locked: bool,
+    #[serde(deserialize_with = "crate::datetime::deserialize")]
+    pub created_at: DateTime<Utc>,
+    #[serde(deserialize_with = "crate::datetime::deserialize")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Structure representing a tag implication: posts tagged with the antecedent are also tagged with
+/// the consequent (e.g. `wolf` implies `canine`).
+#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[non_exhaustive]
+pub struct TagImplication {
+    pub id: u64,
+    pub antecedent_name: String,
+    pub consequent_name: String,
+    pub status: String,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_option")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_option")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Structure representing a tag alias: the antecedent is replaced with the consequent wherever
+/// it's used (e.g. `pussy` is an alias of `vagina`).
+#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[non_exhaustive]
+pub struct TagAlias {
+    pub id: u64,
+    pub antecedent_name: String,
+    pub consequent_name: String,
+    pub status: String,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_option")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_option")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Sort orders for tag searches, used with [`Query::order`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TagOrder {
+    /// Newest first.
+    Date,
+    /// Most used first.
+    Count,
+    /// Alphabetical order.
+    Name,
+}
+
+impl TagOrder {
+    /// Value of the `search[order]` parameter for this order.
+    fn param_value(self) -> &'static str {
+        match self {
+            TagOrder::Date => "date",
+            TagOrder::Count => "count",
+            TagOrder::Name => "name",
+        }
+    }
+}
+
+/// A tag search query, see [`Client::tag_search`]. Only the tags matching every filter are
+/// returned, and an empty query matches every tag.
+///
+/// ```
+/// # use rs621::tag::{Category, Query, TagOrder};
+/// let query = Query::new()
+///     .name_matches("*_fur")
+///     .category(Category::General)
+///     .order(TagOrder::Count);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Query {
+    id: Option<String>,
+    name_matches: Option<String>,
+    category: Option<Category>,
+    order: Option<TagOrder>,
+}
+
+impl Query {
+    /// Creates an empty query, matching every tag.
+    pub fn new() -> Self {
+        Query::default()
+    }
+
+    /// Only the tag with the given ID. Replaces any ID range.
+    pub fn id(mut self, id: u64) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Only the tags whose ID is in `ids`, e.g. to split a full export of the tags between
+    /// several workers, each handling its own range. Replaces any exact ID.
+    pub fn id_range(mut self, ids: RangeInclusive<u64>) -> Self {
+        self.id = Some(format!("{}..{}", ids.start(), ids.end()));
+        self
+    }
+
+    /// Only the tags whose name matches `pattern`, where `*` matches any characters.
+    pub fn name_matches(mut self, pattern: &str) -> Self {
+        self.name_matches = Some(pattern.into());
+        self
+    }
+
+    /// Only the tags of the given category.
+    pub fn category(mut self, category: Category) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    /// Sorts the results. Replaces any order already set.
+    pub fn order(mut self, order: TagOrder) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// Returns the URL encoded search parameters, separated by `&`.
+    fn build_query(&self) -> String {
+        let id = self.id.as_deref().map(|id| query_param("search[id]", id));
+        let name_matches = self
+            .name_matches
+            .as_deref()
+            .map(|pattern| query_param("search[name_matches]", pattern));
+        let category = self
+            .category
+            .map(|category| query_param("search[category]", &u8::from(category).to_string()));
+        let order = self
+            .order
+            .map(|order| query_param("search[order]", order.param_value()));
+
+        [id, name_matches, category, order]
+            .iter()
+            .flatten()
+            .join("&")
+    }
+}
+
+/// Parses a list of tags. The server answers with `{"tags": []}` instead of `[]` when nothing
+/// matched.
+fn parse_tag_list(body: serde_json::Value) -> Rs621Result<Vec<Tag>> {
+    if matches!(body["tags"].as_array(), Some(tags) if tags.is_empty()) {
+        return Ok(Vec::new());
+    }
+
+    serde_json::from_value(body).map_err(|e| Error::Serial(format!("{}", e)))
+}
+
+impl Client {
+    /// Returns the category of each of the given tags, in the same order. Tags that don't exist
+    /// are reported as [`Category::Invalid`].
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// for (name, category) in client.categorize_tags(&["fluffy", "mammal"]).await? {
+    ///     println!("{}: {:?}", name, category);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn categorize_tags(&self, names: &[&str]) -> Rs621Result<Vec<(String, Category)>> {
+        let categories: HashMap<String, Category> = self
+            .get_tags_by_name(names)
+            .await?
+            .into_iter()
+            .map(|tag| (tag.name, tag.category))
+            .collect();
+
+        Ok(names
+            .iter()
+            .map(|&name| {
+                let category = categories.get(name).copied().unwrap_or(Category::Invalid);
+                (String::from(name), category)
+            })
+            .collect())
+    }
+
+    /// Returns the category of each of the given tags, for quick lookups (e.g. to color the tags
+    /// of a search box). Tags that don't exist are left out of the map. The names are looked up
+    /// in a single request, unless there are more than 100 of them.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let names = vec![String::from("fluffy"), String::from("mammal")];
+    /// let categories = client.tag_categories(&names).await?;
+    ///
+    /// println!("{:?}", categories.get("fluffy"));
+    /// # Ok(()) }
+    /// ```
+    pub async fn tag_categories(&self, names: &[String]) -> Rs621Result<HashMap<String, Category>> {
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+        Ok(self
+            .get_tags_by_name(&names)
+            .await?
+            .into_iter()
+            .map(|tag| (tag.name, tag.category))
+            .collect())
+    }
+
+    /// Resolves the tags of a search query, so that they can be displayed along with their
+    /// category and post count. `-` and `~` prefixes are ignored, while meta tags (e.g. `order:`)
+    /// and wildcards are skipped. Tags are returned in the order they appear in the query, and
+    /// those that don't exist are left out.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// for tag in client.search_tag_summary("fluffy -mammal order:score").await? {
+    ///     println!("{}: {:?}", tag.name, tag.category);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn search_tag_summary<T: Into<PostQuery>>(&self, tags: T) -> Rs621Result<Vec<Tag>> {
+        let query_tags = tags.into().tags();
+        let names: Vec<&str> = query_tags
+            .iter()
+            .map(|tag| tag.trim_start_matches(['-', '~']))
+            .filter(|name| !name.is_empty() && !name.contains(':') && !name.contains('*'))
+            .unique()
+            .collect();
+
+        if names.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut found: HashMap<String, Tag> = self
+            .get_tags_by_name(&names)
+            .await?
+            .into_iter()
+            .map(|tag| (tag.name.clone(), tag))
+            .collect();
+
+        Ok(names
+            .into_iter()
+            .filter_map(|name| found.remove(name))
+            .collect())
+    }
+
+    /// Returns the tag with the given name, or `None` if there's no such tag.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// if let Some(tag) = client.tag("fluffy").await? {
+    ///     println!("{:?}: {} posts", tag.category, tag.post_count);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn tag(&self, name: &str) -> Rs621Result<Option<Tag>> {
+        let body = self
+            .get_json_endpoint(&format!(
+                "/tags.json?limit=1&{}",
+                query_param("search[name]", name),
+            ))
+            .await?;
+
+        Ok(parse_tag_list(body)?.into_iter().next())
+    }
+
+    /// Returns the tag with the given ID, or `None` if there's no such tag.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// if let Some(tag) = client.get_tag(12054).await? {
+    ///     println!("{}: {} posts", tag.name, tag.post_count);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn get_tag(&self, id: u64) -> Rs621Result<Option<Tag>> {
+        match self.get_json_endpoint(&format!("/tags/{}.json", id)).await {
+            Ok(body) => serde_json::from_value(body)
+                .map(Some)
+                .map_err(|e| Error::Serial(format!("{}", e))),
+            Err(Error::Http { code: 404, .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns the tags with the given names. Names that don't match any tag are ignored, and the
+    /// order isn't preserved.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// for tag in client.get_tags_by_name(&["fluffy", "mammal"]).await? {
+    ///     println!("{}: {} posts", tag.name, tag.post_count);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn get_tags_by_name(&self, names: &[&str]) -> Rs621Result<Vec<Tag>> {
+        let mut tags = Vec::new();
+
+        for chunk in names.chunks(NAMES_CHUNK_SIZE) {
+            let body = self
+                .get_json_endpoint(&format!(
+                    "/tags.json?limit={}&{}",
+                    NAMES_CHUNK_SIZE,
+                    query_param("search[name]", &chunk.iter().join(",")),
+                ))
+                .await?;
+
+            tags.extend(parse_tag_list(body)?);
+        }
+
+        Ok(tags)
+    }
+
+    /// Returns a Stream over the tags matching the search query.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    /// use rs621::tag::{Category, Query, TagOrder};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let query = Query::new()
+    ///     .name_matches("*_fur")
+    ///     .category(Category::General)
+    ///     .order(TagOrder::Count);
+    /// let mut tags = client.tag_search(query).take(10);
+    ///
+    /// while let Some(tag) = tags.next().await {
+    ///     println!("{}", tag?.name);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn tag_search(&self, query: Query) -> impl Stream<Item = Rs621Result<Tag>> + '_ {
+        let params = query.build_query();
+        let endpoint = if params.is_empty() {
+            String::from("/tags.json?limit=320")
+        } else {
+            format!("/tags.json?limit=320&{}", params)
+        };
+
+        PageStream::new(self, endpoint)
+    }
+
+    /// Returns a Stream over the tags of the given category having at least `min_post_count`
+    /// posts, most used first.
+    ///
+    /// The tags are requested ordered by post count, which is what allows the stream to end as
+    /// soon as one falls below the threshold instead of going through the whole category.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    /// use rs621::tag::Category;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut species = client.tags_in_category(Category::Species, 1000);
+    ///
+    /// while let Some(tag) = species.next().await {
+    ///     println!("{}", tag?.name);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn tags_in_category(
+        &self,
+        category: Category,
+        min_post_count: u64,
+    ) -> impl Stream<Item = Rs621Result<Tag>> + '_ {
+        self.tag_search(Query::new().category(category).order(TagOrder::Count))
+            .take_while(move |res| {
+                future::ready(!matches!(res, Ok(tag) if tag.post_count < min_post_count))
+            })
+    }
+
+    /// Returns a Stream over the tags whose ID is in `ids`, like [`Client::tag_search`] with
+    /// [`Query::id_range`]. Useful to split a full export of the tags between several workers,
+    /// each handling its own range.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let tags: Vec<_> = client.tags_in_id_range(1..=100000).try_collect().await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn tags_in_id_range(
+        &self,
+        ids: RangeInclusive<u64>,
+    ) -> impl Stream<Item = Rs621Result<Tag>> + '_ {
+        self.tag_search(Query::new().id_range(ids))
+    }
+
+    /// Returns a Stream over the tags updated since `since`, newest first. Useful to keep a local
+    /// copy of the tags up to date.
+    ///
+    /// The API can't sort tags by update time, so they're requested newest first by creation
+    /// time, and the stream ends at the first one that wasn't updated since `since`, without
+    /// requesting any further page. Every tag created since then is yielded, but older tags that
+    /// were updated recently may not be.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use chrono::{Duration, Utc};
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut tags = client.tags_updated_since(Utc::now() - Duration::days(1));
+    ///
+    /// while let Some(tag) = tags.next().await {
+    ///     println!("{}", tag?.name);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn tags_updated_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> impl Stream<Item = Rs621Result<Tag>> + '_ {
+        self.tag_search(Query::new().order(TagOrder::Date))
+            .take_while(move |res| future::ready(!matches!(res, Ok(tag) if tag.updated_at < since)))
+    }
+
+    /// Returns a Stream over the active implications whose consequent is `tag`, i.e. the tags
+    /// implying `tag`.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut implications = client.tag_implications("canine");
+    ///
+    /// while let Some(implication) = implications.next().await {
+    ///     println!("{} implies canine", implication?.antecedent_name);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn tag_implications(
+        &self,
+        tag: &str,
+    ) -> impl Stream<Item = Rs621Result<TagImplication>> + '_ {
+        PageStream::new(
+            self,
+            format!(
+                "/tag_implications.json?limit=320&{}&{}",
+                query_param("search[consequent_name]", tag),
+                query_param("search[status]", "active"),
+            ),
+        )
+    }
+
+    /// Returns the tag that `name` is an alias of, or `name` itself if it isn't an alias.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// assert_eq!(client.resolve_tag("pussy").await?, "vagina");
+    /// # Ok(()) }
+    /// ```
+    pub async fn resolve_tag(&self, name: &str) -> Rs621Result<String> {
+        let body = self
+            .get_json_endpoint(&format!(
+                "/tag_aliases.json?limit=1&{}&{}",
+                query_param("search[antecedent_name]", name),
+                query_param("search[status]", "active"),
+            ))
+            .await?;
+
+        // `{"tag_aliases":[]}` when there's no alias
+        if body.get("tag_aliases").is_some() {
+            return Ok(String::from(name));
+        }
+
+        let aliases: Vec<TagAlias> =
+            serde_json::from_value(body).map_err(|e| Error::Serial(format!("{}", e)))?;
+
+        Ok(aliases
+            .into_iter()
+            .next()
+            .map_or_else(|| String::from(name), |alias| alias.consequent_name))
+    }
+
+    /// Whether `a` and `b` are the same tag once aliases are resolved, e.g. `pussy` and `vagina`.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// assert!(client.tags_equivalent("pussy", "vagina").await?);
+    /// # Ok(()) }
+    /// ```
+    pub async fn tags_equivalent(&self, a: &str, b: &str) -> Rs621Result<bool> {
+        if a == b {
+            return Ok(true);
+        }
+
+        Ok(self.resolve_tag(a).await? == self.resolve_tag(b).await?)
+    }
+
+    /// Returns the given tags, followed by all the tags implying any of them. Searching for any of
+    /// the returned tags (e.g. with the `~` prefix) finds posts that are missing the implied tag.
+    ///
+    /// Only direct implications are followed: if `wolf` implies `canis`, which implies `canine`,
+    /// expanding `canine` gives `canis` but not `wolf`.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let tags = client.expand_query_with_implications(&["canine"]).await?;
+    /// let query: Vec<_> = tags.iter().map(|t| format!("~{}", t)).collect();
+    /// # Ok(()) }
+    /// ```
+    pub async fn expand_query_with_implications(&self, tags: &[&str]) -> Rs621Result<Vec<String>> {
+        let mut expanded: Vec<String> = tags.iter().map(|t| String::from(*t)).collect();
+
+        for tag in tags {
+            let implications: Vec<_> = self.tag_implications(tag).try_collect().await?;
+
+            for implication in implications {
+                if !expanded.contains(&implication.antecedent_name) {
+                    expanded.push(implication.antecedent_name);
+                }
+            }
+        }
+
+        Ok(expanded)
+    }
+
+    /// Returns every tag that `tag` implies, directly or not (e.g. `domestic_dog` implies
+    /// `canis`, which implies `canine`, and so on), closest ones first. `tag` itself isn't
+    /// included.
+    ///
+    /// Each tag is looked up once, so implication cycles are harmless. Implications are followed
+    /// up to 20 levels deep, which is far more than any real chain.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let implied = client.implication_tree("domestic_dog").await?;
+    /// assert!(implied.iter().any(|t| t == "mammal"));
+    /// # Ok(()) }
+    /// ```
+    pub async fn implication_tree(&self, tag: &str) -> Rs621Result<Vec<String>> {
+        let mut visited = HashSet::new();
+        visited.insert(String::from(tag));
+
+        let mut implied = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back((String::from(tag), 0));
+
+        while let Some((name, depth)) = queue.pop_front() {
+            if depth >= IMPLICATION_TREE_MAX_DEPTH {
+                continue;
+            }
+
+            let implications: Vec<TagImplication> = PageStream::new(
+                self,
+                format!(
+                    "/tag_implications.json?limit=320&{}&{}",
+                    query_param("search[antecedent_name]", &name),
+                    query_param("search[status]", "active"),
+                ),
+            )
+            .try_collect()
+            .await?;
+
+            for implication in implications {
+                if visited.insert(implication.consequent_name.clone()) {
+                    implied.push(implication.consequent_name.clone());
+                    queue.push_back((implication.consequent_name, depth + 1));
+                }
+            }
+        }
+
+        Ok(implied)
+    }
+
+    /// Returns a Stream over all the posts matching the search query, along with the [`Tag`]s
+    /// they're tagged with. Tags are looked up for a whole page of posts at once, and are only
+    /// looked up once for the lifetime of the stream.
+    ///
+    /// ```no_run
+    /// # use rs621::{client::Client, tag::Category};
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let mut post_stream = client.post_search_with_tags(&["fluffy"][..]).take(3);
+    ///
+    /// while let Some(res) = post_stream.next().await {
+    ///     let (post, tags) = res?;
+    ///     let species = tags.iter().filter(|t| t.category == Category::Species).count();
+    ///
+    ///     println!("Post #{} has {} species tags", post.id, species);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn post_search_with_tags<'a, T: Into<PostQuery>>(
+        &'a self,
+        tags: T,
+    ) -> impl Stream<Item = Rs621Result<(Post, Vec<Tag>)>> + Unpin + 'a {
+        struct State<S> {
+            posts: S,
+            // `None` for names that don't match any tag, so they aren't looked up again
+            cache: HashMap<String, Option<Tag>>,
+            ready: VecDeque<Rs621Result<(Post, Vec<Tag>)>>,
+            ended: bool,
+        }
+
+        let state = State {
+            posts: self.post_search(tags),
+            cache: HashMap::new(),
+            ready: VecDeque::new(),
+            ended: false,
+        };
+
+        Box::pin(stream::unfold(state, move |mut state| async move {
+            loop {
+                if let Some(item) = state.ready.pop_front() {
+                    return Some((item, state));
+                }
+
+                if state.ended {
+                    return None;
+                }
+
+                // wait for a post, then take all the ones that are already available (i.e. the
+                // rest of the page) to look up their tags at once
+                let mut batch = Vec::new();
+                let mut error = None;
+
+                match state.posts.next().await {
+                    Some(Ok(post)) => batch.push(post),
+                    Some(Err(e)) => error = Some(e),
+                    None => state.ended = true,
+                }
+
+                while batch.len() < NAMES_CHUNK_SIZE && error.is_none() && !state.ended {
+                    match state.posts.next().now_or_never() {
+                        Some(Some(Ok(post))) => batch.push(post),
+                        Some(Some(Err(e))) => error = Some(e),
+                        Some(None) => state.ended = true,
+                        None => break,
+                    }
+                }
+
+                let unknown: HashSet<&str> = batch
+                    .iter()
+                    .flat_map(|post| post.tags.iter())
+                    .filter(|name| !state.cache.contains_key(*name))
+                    .collect();
+
+                if !unknown.is_empty() {
+                    let unknown: Vec<&str> = unknown.into_iter().collect();
+
+                    match self.get_tags_by_name(&unknown).await {
+                        Ok(tags) => {
+                            for name in unknown {
+                                state.cache.insert(name.into(), None);
+                            }
+
+                            for tag in tags {
+                                state.cache.insert(tag.name.clone(), Some(tag));
+                            }
+                        }
+
+                        Err(e) => {
+                            // give up on the batch, and end the stream like on other errors
+                            batch.clear();
+                            error = Some(e);
+                        }
+                    }
+                }
+
+                for post in batch {
+                    let tags = post
+                        .tags
+                        .iter()
+                        .filter_map(|name| state.cache.get(name).cloned().flatten())
+                        .collect();
+
+                    state.ready.push_back(Ok((post, tags)));
+                }
+
+                if let Some(e) = error {
+                    state.ready.push_back(Err(e));
+                    state.ended = true;
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    fn tags_endpoint(names: &[&str]) -> String {
+        format!(
+            "/tags.json?limit={}&search%5Bname%5D={}",
+            NAMES_CHUNK_SIZE,
+            urlencoding::encode(&names.join(",")),
+        )
+    }
+
+    fn implication(id: u64, antecedent: &str, consequent: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "antecedent_name": antecedent,
+            "consequent_name": consequent,
+            "status": "active",
+            "created_at": "2020-03-06T15:15:42.000-05:00",
+            "updated_at": null,
+        })
+    }
+
+    /// Page of the active implications whose `side` (`antecedent` or `consequent`) is `tag`.
+    fn implications_endpoint(side: &str, tag: &str, page: u64) -> String {
+        format!(
+            "/tag_implications.json?limit=320&search%5B{}_name%5D={}\
+             &search%5Bstatus%5D=active&page={}",
+            side, tag, page
+        )
+    }
+
+    #[test]
+    fn related_tags_string_or_array() {
+        let mut tag: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/tags_fluffy_mammal.json")).unwrap();
+        let mut tag = tag[0].take();
+
+        let related = |tag: &serde_json::Value| {
+            serde_json::from_value::<Tag>(tag.clone())
+                .unwrap()
+                .related_tags
+        };
+
+        assert_eq!(
+            related(&tag),
+            "fluffy 300 mammal 262 hair 152 anthro 148 fur 147"
+        );
+
+        tag["related_tags"] = serde_json::json!(["fluffy", "mammal", "hair"]);
+        assert_eq!(related(&tag), "fluffy mammal hair");
+
+        tag["related_tags"] = serde_json::json!([["fluffy", 300], ["mammal", 262]]);
+        assert_eq!(related(&tag), "fluffy 300 mammal 262");
+
+        tag["related_tags"] = serde_json::json!([]);
+        assert_eq!(related(&tag), "");
+    }
+
+    #[tokio::test]
+    async fn tag() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let response: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/tags_fluffy_mammal.json")).unwrap();
+        let response = serde_json::json!([response[1]]);
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact("/tags.json?limit=1&search%5Bname%5D=mammal".into()),
+            )
+            .with_body(response.to_string())
+            .create(),
+            mock(
+                "GET",
+                Matcher::Exact("/tags.json?limit=1&search%5Bname%5D=not_a_tag".into()),
+            )
+            .with_body(r#"{"tags":[]}"#)
+            .create(),
+        ];
+
+        assert_eq!(
+            client.tag("mammal").await,
+            Ok(Some(serde_json::from_value(response[0].clone()).unwrap()))
+        );
+        assert_eq!(client.tag("not_a_tag").await, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn get_tag() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let response: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/tags_fluffy_mammal.json")).unwrap();
+
+        let _m = [
+            mock("GET", "/tags/12054.json")
+                .with_body(response[1].to_string())
+                .create(),
+            mock("GET", "/tags/1.json")
+                .with_status(404)
+                .with_body(r#"{"success":false,"reason":"not found"}"#)
+                .create(),
+        ];
+
+        assert_eq!(
+            client.get_tag(12054).await,
+            Ok(Some(serde_json::from_value(response[1].clone()).unwrap()))
+        );
+        assert_eq!(client.get_tag(1).await, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn get_tags_by_name() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let response_json = include_str!("mocked/tags_fluffy_mammal.json");
+        let expected: Vec<Tag> = serde_json::from_str(response_json).unwrap();
+
+        let _m = mock(
+            "GET",
+            Matcher::Exact(tags_endpoint(&["fluffy", "mammal", "not_a_tag"])),
+        )
+        .with_body(response_json)
+        .create();
+
+        assert_eq!(
+            client
+                .get_tags_by_name(&["fluffy", "mammal", "not_a_tag"])
+                .await,
+            Ok(expected)
+        );
+    }
+
+    #[tokio::test]
+    async fn categorize_tags() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock(
+            "GET",
+            Matcher::Exact(tags_endpoint(&["mammal", "not_a_tag", "fluffy"])),
+        )
+        .with_body(include_str!("mocked/tags_fluffy_mammal.json"))
+        .create();
+
+        assert_eq!(
+            client
+                .categorize_tags(&["mammal", "not_a_tag", "fluffy"])
+                .await,
+            Ok(vec![
+                (String::from("mammal"), Category::Species),
+                (String::from("not_a_tag"), Category::Invalid),
+                (String::from("fluffy"), Category::General),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn tag_categories() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        // one name too many for a single request
+        let mut names = vec![String::from("mammal"), String::from("fluffy")];
+        names.extend((0..99).map(|i| format!("not_a_tag_{}", i)));
+
+        let names_str: Vec<&str> = names.iter().map(String::as_str).collect();
+
+        let _m = [
+            mock("GET", Matcher::Exact(tags_endpoint(&names_str[..100])))
+                .with_body(include_str!("mocked/tags_fluffy_mammal.json"))
+                .create(),
+            mock("GET", Matcher::Exact(tags_endpoint(&names_str[100..])))
+                .with_body(r#"{"tags":[]}"#)
+                .create(),
+        ];
+
+        let categories = client.tag_categories(&names).await.unwrap();
+
+        assert_eq!(categories.len(), 2);
+        assert_eq!(categories.get("mammal"), Some(&Category::Species));
+        assert_eq!(categories.get("fluffy"), Some(&Category::General));
+        assert_eq!(categories.get("not_a_tag_0"), None);
+    }
+
+    #[tokio::test]
+    async fn search_tag_summary() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock(
+            "GET",
+            Matcher::Exact(tags_endpoint(&["mammal", "not_a_tag", "fluffy"])),
+        )
+        .with_body(include_str!("mocked/tags_fluffy_mammal.json"))
+        .create();
+
+        let tags = client
+            .search_tag_summary("-mammal not_a_tag ~fluffy fluff* order:score ~mammal")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            tags.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            vec!["mammal", "fluffy"]
+        );
+    }
+
+    #[tokio::test]
+    async fn search_tag_summary_only_meta_tags() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        assert_eq!(
+            client.search_tag_summary("order:score rating:s").await,
+            Ok(vec![])
+        );
+    }
+
+    #[test]
+    fn build_query() {
+        assert_eq!(Query::new().build_query(), "");
+        assert_eq!(Query::new().id(42).build_query(), "search%5Bid%5D=42");
+        assert_eq!(
+            Query::new().id(42).id_range(100..=200).build_query(),
+            "search%5Bid%5D=100..200"
+        );
+        assert_eq!(
+            Query::new()
+                .order(TagOrder::Count)
+                .category(Category::Species)
+                .name_matches("*_fur")
+                .id_range(1..=10)
+                .build_query(),
+            "search%5Bid%5D=1..10&search%5Bname_matches%5D=%2A_fur&search%5Bcategory%5D=5\
+             &search%5Border%5D=count"
+        );
+    }
+
+    #[tokio::test]
+    async fn tag_search() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact(String::from(
+                    "/tags.json?limit=320&search%5Bname_matches%5D=%2A&search%5Border%5D=name\
+                     &page=1",
+                )),
+            )
+            .with_body(include_str!("mocked/tags_fluffy_mammal.json"))
+            .create(),
+            mock(
+                "GET",
+                Matcher::Exact(String::from(
+                    "/tags.json?limit=320&search%5Bname_matches%5D=%2A&search%5Border%5D=name\
+                     &page=2",
+                )),
+            )
+            .with_body(r#"{"tags":[]}"#)
+            .create(),
+        ];
+
+        assert_eq!(
+            client
+                .tag_search(Query::new().name_matches("*").order(TagOrder::Name))
+                .map_ok(|tag| tag.name)
+                .try_collect::<Vec<_>>()
+                .await,
+            Ok(vec![String::from("fluffy"), String::from("mammal")])
+        );
+    }
+
+    #[tokio::test]
+    async fn tags_in_category() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let mut tags: Vec<Tag> =
+            serde_json::from_str(include_str!("mocked/tags_fluffy_mammal.json")).unwrap();
+        tags.reverse();
+        tags[1].category = Category::Species;
+        tags[1].post_count = 10;
+
+        let mut body: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/tags_fluffy_mammal.json")).unwrap();
+        body.as_array_mut().unwrap().reverse();
+        body[1]["category"] = 5.into();
+        body[1]["post_count"] = 10.into();
+
+        // the second page must not be requested since the first one goes below the threshold
+        let _m = mock(
+            "GET",
+            Matcher::Exact(String::from(
+                "/tags.json?limit=320&search%5Bcategory%5D=5&search%5Border%5D=count&page=1",
+            )),
+        )
+        .with_body(body.to_string())
+        .create();
+
+        assert_eq!(
+            client
+                .tags_in_category(Category::Species, 1000)
+                .collect::<Vec<_>>()
+                .await,
+            vec![Ok(tags.remove(0))]
+        );
+    }
+
+    #[tokio::test]
+    async fn tags_in_id_range() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact(String::from(
+                    "/tags.json?limit=320&search%5Bid%5D=100..200&page=1",
+                )),
+            )
+            .with_body(include_str!("mocked/tags_fluffy_mammal.json"))
+            .create(),
+            mock(
+                "GET",
+                Matcher::Exact(String::from(
+                    "/tags.json?limit=320&search%5Bid%5D=100..200&page=2",
+                )),
+            )
+            .with_body(r#"{"tags":[]}"#)
+            .create(),
+        ];
+
+        assert_eq!(
+            client
+                .tags_in_id_range(100..=200)
+                .map_ok(|tag| tag.name)
+                .try_collect::<Vec<_>>()
+                .await,
+            Ok(vec![String::from("fluffy"), String::from("mammal")])
+        );
+    }
+
+    #[tokio::test]
+    async fn tags_updated_since() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        // `mammal` was updated after `fluffy`
+        let mut body: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/tags_fluffy_mammal.json")).unwrap();
+        body.as_array_mut().unwrap().reverse();
+
+        // the second page must not be requested since the first one goes past `since`
+        let _m = mock(
+            "GET",
+            Matcher::Exact(String::from(
+                "/tags.json?limit=320&search%5Border%5D=date&page=1",
+            )),
+        )
+        .with_body(body.to_string())
+        .create();
+
+        let since = "2024-03-12T00:00:00Z".parse().unwrap();
+
+        assert_eq!(
+            client
+                .tags_updated_since(since)
+                .map_ok(|tag| tag.name)
+                .collect::<Vec<_>>()
+                .await,
+            vec![Ok(String::from("mammal"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn get_tags_by_name_no_result() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock("GET", Matcher::Exact(tags_endpoint(&["not_a_tag"])))
+            .with_body(r#"{"tags":[]}"#)
+            .create();
+
+        assert_eq!(client.get_tags_by_name(&["not_a_tag"]).await, Ok(vec![]));
+    }
+
+    #[tokio::test]
+    async fn tags_equivalent() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let endpoint = |tag: &str| {
+            format!(
+                "/tag_aliases.json?limit=1&search%5Bantecedent_name%5D={}\
+                 &search%5Bstatus%5D=active",
+                tag
+            )
+        };
+
+        let _m = [
+            mock("GET", Matcher::Exact(endpoint("pussy")))
+                .with_body(
+                    serde_json::json!([{
+                        "id": 1,
+                        "antecedent_name": "pussy",
+                        "consequent_name": "vagina",
+                        "status": "active",
+                        "created_at": "2020-03-06T15:15:42.000-05:00",
+                        "updated_at": null,
+                    }])
+                    .to_string(),
+                )
+                .create(),
+            mock("GET", Matcher::Exact(endpoint("vagina")))
+                .with_body(r#"{"tag_aliases":[]}"#)
+                .create(),
+            mock("GET", Matcher::Exact(endpoint("penis")))
+                .with_body(r#"{"tag_aliases":[]}"#)
+                .create(),
+        ];
+
+        assert_eq!(
+            client.resolve_tag("pussy").await,
+            Ok(String::from("vagina"))
+        );
+        assert_eq!(client.tags_equivalent("pussy", "vagina").await, Ok(true));
+        assert_eq!(client.tags_equivalent("vagina", "pussy").await, Ok(true));
+        assert_eq!(client.tags_equivalent("penis", "vagina").await, Ok(false));
+    }
+
+    #[tokio::test]
+    async fn expand_query_with_implications() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let endpoint = |tag: &str, page: u64| implications_endpoint("consequent", tag, page);
+
+        let _m = [
+            mock("GET", Matcher::Exact(endpoint("canine", 1)))
+                .with_body(
+                    serde_json::json!([
+                        implication(1, "canis", "canine"),
+                        implication(2, "fox", "canine"),
+                    ])
+                    .to_string(),
+                )
+                .create(),
+            mock("GET", Matcher::Exact(endpoint("canine", 2)))
+                .with_body(r#"{"tag_implications":[]}"#)
+                .create(),
+            mock("GET", Matcher::Exact(endpoint("felid", 1)))
+                .with_body(serde_json::json!([implication(3, "felis", "felid")]).to_string())
+                .create(),
+            mock("GET", Matcher::Exact(endpoint("felid", 2)))
+                .with_body(r#"{"tag_implications":[]}"#)
+                .create(),
+        ];
+
+        assert_eq!(
+            client
+                .expand_query_with_implications(&["canine", "felid"])
+                .await,
+            Ok(vec![
+                String::from("canine"),
+                String::from("felid"),
+                String::from("canis"),
+                String::from("fox"),
+                String::from("felis"),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn implication_tree() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let endpoint = |tag: &str, page: u64| implications_endpoint("antecedent", tag, page);
+
+        // `a` implies `b` and `c`, which both imply `d`, which implies `a` back
+        let implies = |tag: &str, implications: serde_json::Value| {
+            [
+                mock("GET", Matcher::Exact(endpoint(tag, 1)))
+                    .with_body(implications.to_string())
+                    .create(),
+                mock("GET", Matcher::Exact(endpoint(tag, 2)))
+                    .with_body(r#"{"tag_implications":[]}"#)
+                    .create(),
+            ]
+        };
+
+        let _m = [
+            implies(
+                "a",
+                serde_json::json!([implication(1, "a", "b"), implication(2, "a", "c")]),
+            ),
+            implies("b", serde_json::json!([implication(3, "b", "d")])),
+            implies("c", serde_json::json!([implication(4, "c", "d")])),
+            implies("d", serde_json::json!([implication(5, "d", "a")])),
+        ];
+
+        assert_eq!(
+            client.implication_tree("a").await,
+            Ok(vec![
+                String::from("b"),
+                String::from("c"),
+                String::from("d")
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn post_search_with_tags() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let tags: Vec<Tag> =
+            serde_json::from_str(include_str!("mocked/tags_fluffy_mammal.json")).unwrap();
+
+        // two posts tagged `fluffy`, the second one being also tagged `mammal`
+        let mut first: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/id_8595.json")).unwrap();
+        let mut first = first["post"].take();
+        first["tags"] = serde_json::json!({
+            "general": ["fluffy"], "species": [], "character": [], "copyright": [],
+            "artist": [], "invalid": [], "lore": [], "meta": [],
+        });
+        let mut second = first.clone();
+        second["id"] = 8594.into();
+        second["tags"]["species"] = serde_json::json!(["mammal"]);
+
+        let posts_page = serde_json::json!({ "posts": [first, second] }).to_string();
+        let posts: Vec<Post> = serde_json::from_value(serde_json::json!([first, second])).unwrap();
+
+        let m = [
+            mock(
+                "GET",
+                Matcher::Exact("/posts.json?limit=320&page=1&tags=fluffy".into()),
+            )
+            .with_body(posts_page)
+            .create(),
+            mock(
+                "GET",
+                Matcher::Exact("/posts.json?limit=320&page=b8594&tags=fluffy".into()),
+            )
+            .with_body(r#"{"posts":[]}"#)
+            .create(),
+            // both posts are in the same page, so all the tags are fetched at once
+            mock(
+                "GET",
+                Matcher::Regex(r"^/tags\.json\?limit=100&search%5Bname%5D=".into()),
+            )
+            .with_body(include_str!("mocked/tags_fluffy_mammal.json"))
+            .expect(1)
+            .create(),
+        ];
+
+        assert_eq!(
+            client
+                .post_search_with_tags(&["fluffy"][..])
+                .collect::<Vec<_>>()
+                .await,
+            vec![
+                Ok((posts[0].clone(), vec![tags[0].clone()])),
+                Ok((posts[1].clone(), vec![tags[0].clone(), tags[1].clone()])),
+            ]
+        );
+
+        m[2].assert();
+    }
+}
@@ -0,0 +1,176 @@
+use crate::error::Error;
+
+use {
+    super::{
+        client::{query_param, Client},
+        error::Result as Rs621Result,
+    },
+    chrono::{offset::Utc, DateTime},
+    itertools::Itertools,
+    serde::Deserialize,
+    std::collections::HashMap,
+};
+
+/// Maximum number of user IDs looked up in a single request.
+const IDS_CHUNK_SIZE: usize = 100;
+
+/// Structure representing a user.
+#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[non_exhaustive]
+pub struct User {
+    pub id: u64,
+    pub name: String,
+    pub level: u64,
+    /// Name of the level, e.g. `"Member"` or `"Janitor"`.
+    pub level_string: String,
+    pub is_banned: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Parses a list of users. The server answers with `{"users":[]}` when there's none.
+fn parse_user_list(body: serde_json::Value) -> Rs621Result<Vec<User>> {
+    if body.get("users").is_some() {
+        return Ok(Vec::new());
+    }
+
+    serde_json::from_value(body).map_err(|e| Error::Serial(format!("{}", e)))
+}
+
+impl Client {
+    /// Returns the names of the users with the given IDs, e.g. to display the `uploader_id` of
+    /// posts. IDs that don't match any user are left out.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let post = client.get_post(8595).await?;
+    /// let names = client.user_id_to_name(&[post.uploader_id]).await?;
+    ///
+    /// println!("Uploaded by {}", names[&post.uploader_id]);
+    /// # Ok(()) }
+    /// ```
+    pub async fn user_id_to_name(&self, ids: &[u64]) -> Rs621Result<HashMap<u64, String>> {
+        let mut names = HashMap::new();
+
+        for chunk in ids.chunks(IDS_CHUNK_SIZE) {
+            let body = self
+                .get_json_endpoint(&format!(
+                    "/users.json?limit={}&{}",
+                    IDS_CHUNK_SIZE,
+                    query_param("search[id]", &chunk.iter().join(",")),
+                ))
+                .await?;
+
+            names.extend(
+                parse_user_list(body)?
+                    .into_iter()
+                    .map(|user| (user.id, user.name)),
+            );
+        }
+
+        Ok(names)
+    }
+
+    /// Returns the IDs of the users with the given names, e.g. to check them before using them
+    /// in a meta tag. The map is keyed by the names as given, and names that don't match any user
+    /// are left out.
+    ///
+    /// The API can't look up several names at once, so this makes a request per name.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// let ids = client.name_to_id(&["some_user"]).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn name_to_id(&self, names: &[&str]) -> Rs621Result<HashMap<String, u64>> {
+        let mut ids = HashMap::new();
+
+        for name in names {
+            match self
+                .get_json_endpoint(&format!("/users/{}.json", urlencoding::encode(name)))
+                .await
+            {
+                Ok(body) => {
+                    let user: User = serde_json::from_value(body)
+                        .map_err(|e| Error::Serial(format!("{}", e)))?;
+                    ids.insert(String::from(*name), user.id);
+                }
+                Err(Error::Http { code: 404, .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn user_id_to_name() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock(
+            "GET",
+            Matcher::Exact("/users.json?limit=100&search%5Bid%5D=1%2C2%2C3".into()),
+        )
+        .with_body(include_str!("mocked/users_1_2.json"))
+        .create();
+
+        let mut expected = HashMap::new();
+        expected.insert(1, String::from("some_user"));
+        expected.insert(2, String::from("another_user"));
+
+        assert_eq!(client.user_id_to_name(&[1, 2, 3]).await, Ok(expected));
+    }
+
+    #[tokio::test]
+    async fn user_id_to_name_no_result() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = mock(
+            "GET",
+            Matcher::Exact("/users.json?limit=100&search%5Bid%5D=999".into()),
+        )
+        .with_body(r#"{"users":[]}"#)
+        .create();
+
+        assert_eq!(client.user_id_to_name(&[999]).await, Ok(HashMap::new()));
+    }
+
+    #[tokio::test]
+    async fn name_to_id() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let users: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/users_1_2.json")).unwrap();
+
+        let _m = [
+            mock("GET", "/users/some_user.json")
+                .with_body(users[1].to_string())
+                .create(),
+            mock("GET", "/users/nobody.json")
+                .with_status(404)
+                .with_body(r#"{"success":false,"reason":"not found"}"#)
+                .create(),
+        ];
+
+        let mut expected = HashMap::new();
+        expected.insert(String::from("some_user"), 1);
+
+        assert_eq!(
+            client.name_to_id(&["some_user", "nobody"]).await,
+            Ok(expected)
+        );
+    }
+}
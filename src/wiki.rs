@@ -0,0 +1,144 @@
+use {
+    super::{
+        client::{query_param, Client},
+        error::{Error, Result as Rs621Result},
+        tag::Tag,
+    },
+    chrono::{offset::Utc, DateTime},
+    futures::prelude::*,
+    serde::Deserialize,
+};
+
+/// Structure representing a wiki page. The page describing a tag has the tag's name as title.
+#[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
+#[non_exhaustive]
+pub struct WikiPage {
+    pub id: u64,
+    pub title: String,
+    /// The content of the page, in DText.
+    pub body: String,
+    pub other_names: Vec<String>,
+    pub creator_id: u64,
+    pub updater_id: Option<u64>,
+    pub is_locked: bool,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Client {
+    /// Returns the wiki page with the given title, or `None` if there's no such page.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// if let Some(page) = client.wiki_page("fluffy").await? {
+    ///     println!("{}", page.body);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn wiki_page(&self, title: &str) -> Rs621Result<Option<WikiPage>> {
+        let body = self
+            .get_json_endpoint(&format!(
+                "/wiki_pages.json?limit=1&{}",
+                query_param("search[title]", title),
+            ))
+            .await?;
+
+        // like other lists, an empty one can come as `{"wiki_pages": []}`
+        if !body.is_array() {
+            return Ok(None);
+        }
+
+        serde_json::from_value::<Vec<WikiPage>>(body)
+            .map(|pages| pages.into_iter().next())
+            .map_err(|e| Error::Serial(format!("{}", e)))
+    }
+
+    /// Returns the tag with the given name and its wiki page, either of which may not exist. Both
+    /// requests are sent as soon as the rate limit allows, without waiting for each other.
+    ///
+    /// ```no_run
+    /// # use rs621::client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> rs621::error::Result<()> {
+    /// let client = Client::new("https://e926.net", "MyProject/1.0 (by username on e621)")?;
+    ///
+    /// if let (Some(tag), Some(page)) = client.tag_with_wiki("fluffy").await? {
+    ///     println!("{} ({} posts)\n\n{}", tag.name, tag.post_count, page.body);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn tag_with_wiki(&self, name: &str) -> Rs621Result<(Option<Tag>, Option<WikiPage>)> {
+        future::try_join(self.tag(name), self.wiki_page(name)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn wiki_page() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact("/wiki_pages.json?limit=1&search%5Btitle%5D=fluffy".into()),
+            )
+            .with_body(include_str!("mocked/wiki_fluffy.json"))
+            .create(),
+            mock(
+                "GET",
+                Matcher::Exact("/wiki_pages.json?limit=1&search%5Btitle%5D=not_a_page".into()),
+            )
+            .with_body("[]")
+            .create(),
+        ];
+
+        let page = client.wiki_page("fluffy").await.unwrap().unwrap();
+        assert_eq!(page.id, 1826);
+        assert_eq!(page.other_names, vec!["floofy"]);
+
+        assert_eq!(client.wiki_page("not_a_page").await, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn tag_with_wiki() {
+        let client = Client::new(&mockito::server_url(), b"rs621/unit_test").unwrap();
+
+        let tags: serde_json::Value =
+            serde_json::from_str(include_str!("mocked/tags_fluffy_mammal.json")).unwrap();
+        let fluffy = tags
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|tag| tag["name"] == "fluffy")
+            .cloned()
+            .unwrap();
+
+        let _m = [
+            mock(
+                "GET",
+                Matcher::Exact("/tags.json?limit=1&search%5Bname%5D=fluffy".into()),
+            )
+            .with_body(serde_json::json!([fluffy]).to_string())
+            .create(),
+            mock(
+                "GET",
+                Matcher::Exact("/wiki_pages.json?limit=1&search%5Btitle%5D=fluffy".into()),
+            )
+            .with_body(include_str!("mocked/wiki_fluffy.json"))
+            .create(),
+        ];
+
+        let (tag, page) = client.tag_with_wiki("fluffy").await.unwrap();
+        assert_eq!(tag.map(|tag| tag.name), Some(String::from("fluffy")));
+        assert_eq!(page.map(|page| page.title), Some(String::from("fluffy")));
+    }
+}